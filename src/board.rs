@@ -7,74 +7,167 @@ You are free to modify anything, including the function parameters,
 the code is provided as a support if desired.
 */
 
+mod bindings;
 mod command;
+mod config;
 mod constant;
+mod daily;
 mod display;
 mod error;
+mod export;
+mod game_code;
 mod game_settings;
+mod geometry;
+mod history;
 mod input;
+mod item;
+mod json;
+mod move_set;
+mod noise;
+mod palette;
+mod parser;
 mod position;
+mod prng;
+mod target;
+mod tile;
+mod tracker;
 mod zmove;
 
 // my rust file from board/
 use command::*;
-use constant::*;
-use display::*;
 use error::*;
 use input::*;
 
 // specific struct from my files
+use bindings::Bindings;
+use config::{Config, Profile};
+use daily::{DailyHistory, DailyResult};
 use game_settings::GameSettings;
+use geometry::Geometry;
+use history::GameEvent;
+use item::{Inventory, Item, ItemResult};
+use move_set::MoveSet;
+use noise::HintNoise;
+use palette::Palette;
 use position::Position;
+use prng::Xoshiro256StarStar;
+use target::Target;
+use tile::Tile;
+use tracker::{Heatmap, SearchRecord, Tracker};
 use zmove::{Direction, Zmove};
 
 // things from imported crates
-use matches::assert_matches;
-use rand::{rngs::StdRng, RngCore, SeedableRng};
-use regex::Regex;
+use rand::SeedableRng;
 use std::str::FromStr;
 use termcolor::Color;
 
 /// the board structure that is the basis of all the treaure hunt
 ///
 /// # Attributes
-/// * `player_color` - the color representing the player, the closer to blue, the harder the game
+/// * `seed` - the seed the board was generated from
+/// * `player_color` - the color representing the player, the closer to the water color, the harder the game
 /// * `player_coordinates` - the position of the player on the board
 /// * `treasure_coordinates` - the treasure position on the board
-/// * `rng` - the standar RNG used to fix randomness during a game
-/// * `player_tile` - the char that will represent the user on the map (when it was a str you could enter emojis)
-/// * `tracker` - the 2d bool map of where the player has already searched
-#[derive(Debug)]
+/// * `rng` - the pinned RNG used to fix randomness during a game, the noisy hints are drawn with it, see `prng`
+/// * `player_tile` - the grapheme that will represent the user on the map, it can be an emoji again
+/// * `tracker` - where the player has already searched, and what each search told
+/// * `is_treasure_found` - true once the treasure has been found, used to reveal it in exports
+/// * `history` - every move and search done during the game, in order
+/// * `move_set` - the shapes of the moves the player is allowed to do
+/// * `show_reach` - if the cells in reach and the zmove rays are drawn
+/// * `bindings` - the custom key bindings used to read the commands
+/// * `geometry` - the size of the board and the reach of a move
+/// * `distance_hints` - if a failed search tells the distance to the treasure
+/// * `direction_hints` - if a failed search tells the direction of the treasure
+/// * `zmoves_allowed` - if the zmoves and the chains can be used
+/// * `fog_radius` - how far the player sees, the whole board is drawn if it's `None`
+/// * `inventory` - the uses left of each item
+/// * `hint_noise` - how the distance hints are blurred, they are exact if it's `None`
+/// * `show_candidates` - if the cells that can hold the treasure are drawn with their odds
+/// * `turn` - the number of commands that changed the game, the moves, searches and item uses,
+///   the searches are stamped with it, see `Board::count_turn`
+/// * `heatmap` - how the searched cells are colored, by their hint, by recency, or plainly
+/// * `pan` - how far the view is from the player, in cells, see `Board::viewport`
+/// * `terminal` - the columns and lines of the terminal the board is drawn in, `None` if unknown
+/// * `palette` - the colors of the water and of the searched cells
+#[derive(Debug, Clone)]
 pub struct Board {
+    seed: u64,
     player_color: Color,
     player_coordinates: Position,
     treasure_coordinates: Position,
-    rng: rand::prelude::StdRng,
-    player_tile: char,
-    //those 2 are hella dangereous, it's better to ignore them completely
-    //board_width: u32,
-    //board_height: u32,
-    tracker: Vec<Vec<bool>>,
+    rng: Xoshiro256StarStar,
+    player_tile: Tile,
+    tracker: Tracker,
+    is_treasure_found: bool,
+    history: Vec<GameEvent>,
+    move_set: MoveSet,
+    show_reach: bool,
+    bindings: Bindings,
+    geometry: Geometry,
+    distance_hints: bool,
+    direction_hints: bool,
+    zmoves_allowed: bool,
+    fog_radius: Option<u32>,
+    inventory: Inventory,
+    hint_noise: Option<HintNoise>,
+    show_candidates: bool,
+    turn: u32,
+    heatmap: Heatmap,
+    pan: (i64, i64),
+    terminal: Option<(u32, u32)>,
+    palette: Palette,
 }
 
 /// where I hid all my `Board`'s function's implementation
 impl Board {
     /// Gets a new pair of random coordinates
     ///
-    /// respects the board proportions
+    /// respects the board proportions, every cell has exactly the same odds, see `Xoshiro256StarStar::below`
     /// this method is static, it uses the rng generator given in argument
     /// It's static for constructor reasons, you should always pass the board's rng field
     ///
     /// # Arguments
     ///
-    /// * `rng` - a mutable reference to the rand::prelude::StdRng used to get the coordinates
+    /// * `rng` - a mutable reference to the pinned generator used to get the coordinates
+    /// * `geometry` - the board the coordinates have to fit in
     ///
     /// # Returns
     ///
-    /// * A u32 pair that have been modulated to fit in the Board
-    fn random_coordinates(rng: &mut rand::prelude::StdRng) -> Position {
-        // the cast is needed for coordinate_modulo
-        Board::coordinate_modulo((rng.next_u32() as i64, rng.next_u32() as i64))
+    /// * A Position drawn uniformly in the Board
+    fn random_coordinates(rng: &mut Xoshiro256StarStar, geometry: &Geometry) -> Position {
+        Position {
+            x: rng.below(geometry.width),
+            y: rng.below(geometry.height),
+        }
+    }
+
+    /// Gets the starting coordinates of the player and of the treasure
+    ///
+    /// the player is drawn first, then the treasure is drawn again until it's far enough,
+    /// so it's uniform among the cells at least `spawn_distance` away
+    /// the geometry checked that such a cell exists, on a torus every cell has one
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - a mutable reference to the pinned generator used to get the coordinates
+    /// * `geometry` - the board, with the spawn distance
+    ///
+    /// # Returns
+    ///
+    /// * The player and the treasure Positions
+    fn spawn_coordinates(
+        rng: &mut Xoshiro256StarStar,
+        geometry: &Geometry,
+    ) -> (Position, Position) {
+        let player = Board::random_coordinates(rng, geometry);
+        loop {
+            let treasure = Board::random_coordinates(rng, geometry);
+            let dist = Position::get_shortest_dist(player.get_xy_dists(&treasure), geometry);
+            if dist >= geometry.spawn_distance {
+                return (player, treasure);
+            }
+        }
     }
 
     /// Sets the player coordinate to the one given in argument
@@ -88,8 +181,8 @@ impl Board {
     /// # Arguments
     ///
     /// * `i64_coordinates` - a i64 pair representing a posiiton
-    fn set_player_coordinates(&mut self, i64_coordinates: (i64, i64)) -> () {
-        self.player_coordinates = Board::coordinate_modulo(i64_coordinates);
+    fn set_player_coordinates(&mut self, i64_coordinates: (i64, i64)) {
+        self.player_coordinates = Board::coordinate_modulo(i64_coordinates, &self.geometry);
     }
 
     /// Applies a mod of width and height on the given coordinate
@@ -103,18 +196,19 @@ impl Board {
     /// # Arguments
     ///
     /// * `i64_pair` - the i64 pair that will be modulated to become a coordinate pair, we use i64 as all u32 can fit in it
+    /// * `geometry` - the board the pair is wrapped on
     ///
     /// # Returns
     /// * A Position that fits in the board
-    fn coordinate_modulo(i64_pair: (i64, i64)) -> Position {
+    fn coordinate_modulo(i64_pair: (i64, i64), geometry: &Geometry) -> Position {
         Position {
-            x: (i64_pair.0.rem_euclid(Board::DEFAULT_BOARD_WIDTH as i64)) as u32,
-            y: (i64_pair.1.rem_euclid(Board::DEFAULT_BOARD_HEIGHT as i64)) as u32,
+            x: (i64_pair.0.rem_euclid(geometry.width as i64)) as u32,
+            y: (i64_pair.1.rem_euclid(geometry.height as i64)) as u32,
         }
     }
 
-    fn is_in_board(position: &Position) -> bool {
-        (position.x < Board::DEFAULT_BOARD_WIDTH) && (position.y < Board::DEFAULT_BOARD_HEIGHT)
+    fn is_in_board(position: &Position, geometry: &Geometry) -> bool {
+        (position.x < geometry.width) && (position.y < geometry.height)
     }
 
     /// basic default constructor
@@ -129,17 +223,36 @@ impl Board {
     ///
     /// * a new Board instance
     fn new(game_settings: GameSettings) -> Board {
-        let mut rng_to_move = StdRng::seed_from_u64(game_settings.seed); // not suitable for crypto, but this isn't crypto
+        let mut rng_to_move = Xoshiro256StarStar::seed_from_u64(game_settings.seed); // the same board in every version
+        let geometry = game_settings.geometry;
+        let (start_coordinates, treasure_coordinates) =
+            Board::spawn_coordinates(&mut rng_to_move, &geometry);
         Board {
+            seed: game_settings.seed,
             player_color: game_settings.player_color,
             player_tile: game_settings.player_tile,
-            player_coordinates: Board::random_coordinates(&mut rng_to_move),
-            treasure_coordinates: Board::random_coordinates(&mut rng_to_move),
+            history: vec![GameEvent::Start(start_coordinates.clone())],
+            player_coordinates: start_coordinates,
+            treasure_coordinates,
             rng: rng_to_move, // the rng is moved here
-            tracker: vec![
-                vec![false; Board::DEFAULT_BOARD_HEIGHT as usize];
-                Board::DEFAULT_BOARD_WIDTH as usize
-            ],
+            tracker: Tracker::new(&geometry),
+            is_treasure_found: false,
+            move_set: game_settings.move_set,
+            show_reach: false,
+            bindings: Bindings::default(),
+            geometry,
+            distance_hints: game_settings.distance_hints,
+            direction_hints: game_settings.direction_hints,
+            zmoves_allowed: game_settings.zmoves_allowed,
+            fog_radius: game_settings.fog_radius,
+            inventory: Inventory::default(),
+            hint_noise: game_settings.hint_noise,
+            show_candidates: false,
+            turn: 0,
+            heatmap: Heatmap::Off,
+            pan: (0, 0),
+            terminal: None,
+            palette: game_settings.palette,
         }
     }
 
@@ -151,10 +264,14 @@ impl Board {
     /// * `Ok` - if game closed normally
     /// * `Err` - if the game did not work properly
     pub fn play_game() -> Result<(), std::io::Error> {
+        let bindings = Board::load_bindings();
+        let mut config = Board::load_config();
+
         //while game not closing start a new game
         let mut is_game_closing: bool = false;
         while !is_game_closing {
-            let mut this_board: Board = Board::init_game();
+            let mut this_board: Board = Board::init_game(&mut config);
+            this_board.bindings = bindings.clone();
 
             // while game is not over play turn
             let mut is_game_over: bool = false;
@@ -168,6 +285,135 @@ impl Board {
         Ok(()) // the game ended normally
     }
 
+    /// the daily challenge, the same board for every player on a day
+    /// there is no settings menu, and the first game of the day is recorded in the history,
+    /// a chosen day is only practice, it's never recorded so the streaks can't be forged
+    ///
+    /// # Arguments
+    /// * `date` - the day to play, today if it's none, written `YYYY-MM-DD`
+    ///
+    /// # Returns
+    /// * `Ok` - if the game closed normally
+    /// * `Err` - if the date isn't valid
+    pub fn play_daily(date: Option<&str>) -> Result<(), std::io::Error> {
+        let is_chosen = date.is_some();
+        let date = match date {
+            Some(date) => chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|err| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("the date '{}' isn't a YYYY-MM-DD date, {}", date, err),
+                )
+            })?,
+            None => chrono::Local::now().date_naive(),
+        };
+        let path = DailyHistory::path();
+        let mut history = match path.as_deref().map(DailyHistory::from_file) {
+            Some(Ok(history)) => history,
+            Some(Err(err)) => {
+                println!("Your daily history could not be read, {}.", err);
+                DailyHistory::default()
+            }
+            None => DailyHistory::default(),
+        };
+
+        display::print_daily_intro(date, history.result_of(date));
+        let mut this_board = Board::new(daily::daily_settings(date));
+        this_board.bindings = Board::load_bindings();
+        let mut is_game_over: bool = false;
+        while !is_game_over {
+            is_game_over = this_board.play_turn();
+        }
+
+        if is_chosen {
+            display::print_daily_not_recorded();
+        } else if history.record(DailyResult {
+            date,
+            won: this_board.is_treasure_found,
+            turns: this_board.turn,
+        }) {
+            match path.as_deref().map(|path| history.save(path)) {
+                Some(Ok(_)) => (),
+                Some(Err(err)) => println!("Your daily history could not be saved, {}.", err),
+                None => {
+                    println!("Your daily history could not be saved, there is no data directory.")
+                }
+            }
+        }
+        display::print_daily_history(&history, date);
+
+        this_board.offer_export(); // there is no replay, the board of the day was played
+        display::print_goodbye();
+        Ok(())
+    }
+
+    /// loads the custom key bindings, if the player wrote a bindings file
+    /// a wrong file is reported and ignored, the game is still playable with the built-in commands
+    ///
+    /// # Returns
+    /// * `Bindings` - the loaded bindings, or none
+    fn load_bindings() -> Bindings {
+        if !std::path::Path::new(Board::BINDINGS_PATH).exists() {
+            return Bindings::default();
+        }
+        match Bindings::from_file(Board::BINDINGS_PATH) {
+            Ok(bindings) => bindings,
+            Err(err) => {
+                display::print_bindings_error(Board::BINDINGS_PATH, &err.to_string());
+                Bindings::default()
+            }
+        }
+    }
+
+    /// loads the configuration file, if the player wrote or saved one
+    /// a wrong file is reported and ignored, the game starts with the default settings,
+    /// a wrong profile is reported and can't be loaded, the others can
+    ///
+    /// # Returns
+    /// * `Config` - the loaded configuration, or an empty one
+    fn load_config() -> Config {
+        let path = match Config::path() {
+            Some(path) if path.exists() => path,
+            _ => return Config::default(),
+        };
+        match Config::from_file(&path) {
+            Ok(config) => {
+                for warning in config.warnings() {
+                    display::print_profile_warning(&path.to_string_lossy(), &warning);
+                }
+                config
+            }
+            Err(err) => {
+                display::print_config_error(&path.to_string_lossy(), &err.to_string());
+                Config::default()
+            }
+        }
+    }
+
+    /// saves the settings as a profile, it becomes the startup one
+    /// a failure is only reported, the settings are still used for this game
+    ///
+    /// # Arguments
+    /// * `config` - the configuration the profile is added to
+    /// * `name` - the name of the profile, an existing one is replaced
+    /// * `game_settings` - the settings to save
+    fn save_profile(config: &mut Config, name: String, game_settings: &GameSettings) {
+        let path = match Config::path() {
+            Some(path) => path,
+            None => {
+                println!("The profile could not be saved, there is no config directory.");
+                return;
+            }
+        };
+        config
+            .profiles
+            .insert(name.clone(), Profile::from_settings(game_settings));
+        config.profile = Some(name.clone());
+        match config.save(&path) {
+            Ok(_) => display::print_profile_saved(&name, &path.to_string_lossy()),
+            Err(err) => println!("The profile could not be saved, {}.", err),
+        }
+    }
+
     /// the handling of the ending
     /// notably if a new game wil be started or
     /// if the game will close
@@ -175,15 +421,41 @@ impl Board {
     /// # Returns
     /// * `bool` - true if the game will close, false if a new game will be launched
     fn end_of_game(&self) -> bool {
+        self.offer_export();
         display::print_end_screen();
 
         match input::get_yes_no_choice().as_str() {
-            "yes" | "y" => return false,
-            "no" | "n" => return true,
+            "yes" | "y" => false,
+            "no" | "n" => true,
             _ => panic!("an unexpected answer was given during the ending of the game"),
         }
     }
 
+    /// asks the player if the finished game should be exported, and exports it
+    fn offer_export(&self) {
+        display::print_export_offer();
+        if let "yes" | "y" = input::get_yes_no_choice().as_str() {
+            // the treasure can only be shown if the game is won
+            self.export_board_and_report(self.is_treasure_found);
+            match self.export_timeline() {
+                Ok(_) => display::print_timeline_export_done(Board::EXPORT_GIF_PATH),
+                Err(err) => println!("The animation export failed, {}.", err),
+            }
+        }
+    }
+
+    /// exports the board images and tells the player how it went
+    /// an export failure is not a reason to stop the game, it's only reported
+    ///
+    /// # Arguments
+    /// * `reveal_treasure` - if the treasure should be drawn, it should only be after a win
+    fn export_board_and_report(&self, reveal_treasure: bool) {
+        match self.export_board(reveal_treasure) {
+            Ok(_) => display::print_export_done(Board::EXPORT_SVG_PATH, Board::EXPORT_PNG_PATH),
+            Err(err) => println!("The export failed, {}.", err),
+        }
+    }
+
     /// the hendling of a turn
     /// notably the board printing
     /// the choice of this turn action and it's handling
@@ -192,65 +464,239 @@ impl Board {
     /// * `bool` - true if the current game is finished, true if it isn't
     fn play_turn(&mut self) -> bool {
         let mut will_game_end: bool = false;
+        // the terminal may have been resized since the last turn
+        self.terminal = display::terminal_dimensions();
         match self.print_game_board() {
             Ok(_) => (), //do nothing,
             Err(_) => println!("The board printing failed, you are now playing blind sorry."),
         }
+        if self.show_candidates {
+            display::print_best_candidate(self.best_candidate());
+        }
 
         display::print_turn_command();
+        display::print_inventory(&self.inventory);
+        display::print_bindings(&self.bindings);
 
-        match get_choice_command() {
+        let command = get_choice_command(&self.bindings);
+        if let Err(err) = self.check_rules(&command) {
+            println!("{}", input::format_error(&err));
+            return will_game_end;
+        }
+        // the searches of this command are stamped with its turn
+        let events = self.history.len();
+        self.turn += 1;
+        match command {
             Command::AskTeleport => self.teleport(), // handle teleport input and logic
             Command::Search => will_game_end = self.search_player_position(), // handle search logic, might finish game
             Command::Quit => will_game_end = true,                            // game is now over
             Command::AskZmove => self.zmove(), // handle zmove input and logic
             Command::Zmove(zmove) => self.zmove_logic(zmove), // handle zmove logic only
+            Command::Export => self.export_board_and_report(false), // treasure stays hidden mid game
+            Command::Sequence { zmoves, search } => will_game_end = self.sequence(zmoves, search), // might finish game
+            Command::ToggleReach => {
+                self.show_reach = !self.show_reach;
+                display::print_reach_toggled(self.show_reach)
+            }
+            Command::ToggleCandidates => {
+                self.show_candidates = !self.show_candidates;
+                display::print_candidates_toggled(self.show_candidates)
+            }
+            Command::CycleHeatmap => {
+                self.heatmap = self.heatmap.next();
+                display::print_heatmap_changed(self.heatmap)
+            }
+            Command::ListSearches => {
+                display::print_searches(self.tracker.searches(), self.tracker.searched_count())
+            }
+            Command::Pan(direction) => match self.pan_view(direction) {
+                true => display::print_view_panned(&self.view_center()),
+                false => display::print_view_whole(),
+            },
+            Command::UseItem(item) => will_game_end = self.use_item(item), // might finish game
         }
+        self.count_turn(events);
         will_game_end
     }
 
-    fn zmove_logic(&mut self, zmove: Zmove) -> () {
-        // that moment when all the i64 things are actually used
-        let target_position = Board::coordinate_modulo((
-            self.player_coordinates.to_i64().0 + zmove.get_vector().0,
-            self.player_coordinates.to_i64().1 + zmove.get_vector().1,
-        ));
+    /// takes back the turn of a command that changed nothing in the game,
+    /// like a toggle, a pan, a quit or a refused move, every move, search and item use is in the history
+    ///
+    /// # Arguments
+    /// * `events` - the length of the history before the command
+    fn count_turn(&mut self, events: usize) {
+        if self.history.len() == events {
+            self.turn -= 1;
+        }
+    }
+
+    /// checks that a command is allowed by the rule toggles of the game
+    ///
+    /// # Arguments
+    /// * `command` - the command the player chose
+    ///
+    /// # Returns
+    /// * `Ok(_)` - if the command can be done
+    /// * `Err(BoardError::InvalidCommand)` - if the command uses zmoves and they are off
+    fn check_rules(&self, command: &Command) -> Result<(), BoardError> {
+        match command {
+            Command::AskZmove | Command::Zmove(_) | Command::Sequence { .. }
+                if !self.zmoves_allowed =>
+            {
+                Err(BoardError::InvalidCommand(ErrorContext::new(
+                    "The zmoves are off in this game, use move",
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
 
+    fn zmove_logic(&mut self, zmove: Zmove) {
         //delegate everything to teleport logic
-        // can give IvalidMove error, technically it should not be possible due to zmove validity
-        match self.teleport_logic(&target_position) {
-            Ok(_) => (),
-            Err(_) => panic!("impossible error, message from zmove logic"),
+        // can give IvalidMove error if the zmove doesn't fit the move set
+        if let Err(err) = self.try_zmove(&zmove) {
+            println!("{}", input::format_error(&err));
         }
     }
 
-    fn zmove(&mut self) -> () {
+    /// does a zmove without printing anything
+    ///
+    /// # Arguments
+    /// * `zmove` - the zmove to do from the player position
+    ///
+    /// # Returns
+    /// * `Ok(_)` - if the zmove could be done
+    /// * `Err(BoardError::InvalidMove)` - if the zmove doesn't fit the move set
+    fn try_zmove(&mut self, zmove: &Zmove) -> Result<(), BoardError> {
+        // that moment when all the i64 things are actually used
+        let target_position = Board::coordinate_modulo(
+            (
+                self.player_coordinates.to_i64().0 + zmove.get_vector().0,
+                self.player_coordinates.to_i64().1 + zmove.get_vector().1,
+            ),
+            &self.geometry,
+        );
+        self.teleport_logic(&target_position)
+    }
+
+    fn zmove(&mut self) {
+        let zmove_speeds = self.move_set.zmove_speeds(&self.geometry);
+        display::print_move_set_zmoves(&self.move_set, &zmove_speeds);
+        if zmove_speeds.is_empty() {
+            return; // no zmove fits, the player has to use move
+        }
+        if self.show_reach {
+            display::print_zmove_rays();
+            if self
+                .print_game_board_with_overlay(&self.zmove_rays_overlay())
+                .is_err()
+            {
+                println!("The zmove rays printing failed, you can still zmove.");
+            }
+        }
+
         //input move and recenter
-        let zmove: Zmove = input::get_zmove();
+        let zmove: Zmove = input::get_zmove(&self.move_set, &self.geometry);
         self.zmove_logic(zmove)
         //move done posiiton changed
     }
 
+    /// the positions crossed by a chain of zmoves, in order
+    ///
+    /// # Arguments
+    /// * `zmoves` - the chain, starting from the player position
+    ///
+    /// # Returns
+    /// * `Vec<Position>` - every crossed position, the last one is where the chain ends
+    fn sequence_path(&self, zmoves: &[Zmove]) -> Vec<Position> {
+        let mut start = self.player_coordinates.to_i64();
+        let mut path: Vec<Position> = Vec::new();
+        for zmove in zmoves {
+            path.extend(zmove.get_path_vectors().iter().map(|vector| {
+                Board::coordinate_modulo((start.0 + vector.0, start.1 + vector.1), &self.geometry)
+            }));
+            start = (
+                start.0 + zmove.get_vector().0,
+                start.1 + zmove.get_vector().1,
+            );
+        }
+        path
+    }
+
+    /// checks that every zmove of a chain fits the move set, so the chain is done whole or not at all
+    ///
+    /// # Arguments
+    /// * `zmoves` - the chain
+    ///
+    /// # Returns
+    /// * `Ok(_)` - if every zmove fits
+    /// * `Err(BoardError::InvalidMove)` - about the first zmove that doesn't fit
+    fn check_sequence(&self, zmoves: &[Zmove]) -> Result<(), BoardError> {
+        match zmoves
+            .iter()
+            .position(|zmove| !self.move_set.allows_zmove(zmove, &self.geometry))
+        {
+            Some(index) => Err(BoardError::InvalidMove(ErrorContext::new(&format!(
+                "The zmove number {} doesn't fit the {} move set, nothing was done",
+                index + 1,
+                self.move_set
+            )))),
+            None => Ok(()),
+        }
+    }
+
+    /// the handling of a chain of zmoves, the path is shown before the zmoves are done
+    /// the chain was already validated as a whole when it was parsed
+    ///
+    /// # Arguments
+    /// * `zmoves` - the zmoves to do in order
+    /// * `search` - if a search is done at the end
+    ///
+    /// # Returns
+    /// * `bool` - the boolean that tells if the game is won
+    fn sequence(&mut self, zmoves: Vec<Zmove>, search: bool) -> bool {
+        if let Err(err) = self.check_sequence(&zmoves) {
+            println!("{}", input::format_error(&err));
+            return false;
+        }
+        display::print_sequence_preview();
+        if self
+            .print_game_board_with_overlay(&self.path_overlay(&self.sequence_path(&zmoves)))
+            .is_err()
+        {
+            println!("The path printing failed, your zmoves are still done.");
+        }
+
+        for zmove in zmoves {
+            self.zmove_logic(zmove);
+        }
+        search && self.search_player_position()
+    }
+
     /// the handling of the teleport action
     /// teleport corresponds to the move command in the doc
     /// I decided to not call it a move, as it's a teleport
-    fn teleport(&mut self) -> () {
+    fn teleport(&mut self) {
         let mut is_position_validated = false;
         while !is_position_validated {
             //input move and recenter
-            let mut target_position: Position = input::get_position_for_teleport();
-
-            //verif if is oob
-            if !Board::is_in_board(&target_position) {
+            let target: Target = input::get_target_for_teleport();
+            let target_position = Board::coordinate_modulo(
+                target.resolve(&self.player_coordinates, &self.geometry),
+                &self.geometry,
+            );
+
+            //verif if is oob, only absolute positions are typed by hand, the relative ones are expected to wrap
+            if let Some(typed_position) = target
+                .absolute_position()
+                .filter(|typed_position| !Board::is_in_board(typed_position, &self.geometry))
+            {
                 // oob handling
-                let corrected_target_position = Board::coordinate_modulo(target_position.to_i64());
                 display::print_special_corrector_message(
-                    &target_position,           // oob value
-                    &corrected_target_position, // ib value
+                    &typed_position,  // oob value
+                    &target_position, // ib value
                 );
 
-                target_position = corrected_target_position; // preparation if yes
-
                 match input::get_yes_no_choice().as_str() {
                     "yes" | "y" => (),      // continue handling as nothing happened
                     "no" | "n" => continue, // this should restart the while loop
@@ -259,8 +705,7 @@ impl Board {
             }
             match self.teleport_logic(&target_position) {
                 Ok(_) => is_position_validated = true,
-                Err(BoardError::InvalidMove(s)) => println!("{}", s),
-                Err(_) => panic!("impossible error message from teleport"),
+                Err(err) => println!("{}", input::format_error(&err)),
             }
         }
 
@@ -280,15 +725,26 @@ impl Board {
     fn teleport_logic(&mut self, target: &Position) -> Result<(), BoardError> {
         // the target position will always be in board, even if not
         // the second point might be confusing but it's true
-        if Position::is_dist_legal(self.player_coordinates.get_xy_dists(target)) {
+        if self
+            .move_set
+            .is_legal(&self.player_coordinates, target, &self.geometry)
+        {
             //if legal do the move
             //set player coordinate will apply the modulus
             self.set_player_coordinates(target.to_i64());
+            self.history
+                .push(GameEvent::Move(self.player_coordinates.clone()));
             Ok(())
-        } else {
+        } else if self.move_set == MoveSet::Classic {
             Err(BoardError::InvalidMove(
-                "You can't do this move, it's too far".to_string(),
+                ErrorContext::new("You can't do this move, it's too far")
+                    .expecting(0, self.geometry.reach as i64),
             ))
+        } else {
+            Err(BoardError::InvalidMove(ErrorContext::new(&format!(
+                "You can't do this move, it doesn't fit the {} move set",
+                self.move_set
+            ))))
         }
     }
 
@@ -301,42 +757,267 @@ impl Board {
     /// # Returns
     /// * `bool` - the boolean that tells if the game is won
     fn search_player_position(&mut self) -> bool {
-        if self.player_coordinates == self.treasure_coordinates {
-            display::print_win_screen();
-            return true;
+        match self.search_logic() {
+            None => {
+                display::print_win_screen(self.seed);
+                true
+            }
+            Some(dist_to_tresure) => {
+                match self.distance_hints {
+                    true => display::print_found_nothing(dist_to_tresure),
+                    false => display::print_found_nothing_without_hint(),
+                }
+                if self.distance_hints && self.hint_noise.is_some() {
+                    display::print_hint_noise_warning();
+                }
+                if let Some(direction) = self.treasure_direction().filter(|_| self.direction_hints)
+                {
+                    display::print_treasure_direction(direction);
+                }
+                false
+            }
         }
+    }
+
+    /// the search itself, without any printing
+    /// the tracker and the history are updated here, and the hints told when they are on
+    ///
+    /// # Returns
+    /// * `None` - if the treasure was found
+    /// * `Some(u32)` - the shortest distance to the treasure if it wasn't found, blurred by the hint noise
+    fn search_logic(&mut self) -> Option<u32> {
+        self.history
+            .push(GameEvent::Search(self.player_coordinates.clone()));
 
-        self.tracker[self.player_coordinates.x as usize][self.player_coordinates.y as usize] = true;
+        if self.player_coordinates == self.treasure_coordinates {
+            self.is_treasure_found = true;
+            return None;
+        }
 
-        let dist_to_tresure = Position::get_shortest_dist(
+        let dist = Position::get_shortest_dist(
             self.player_coordinates
                 .get_xy_dists(&self.treasure_coordinates),
+            &self.geometry,
+        );
+        let max_dist = Geometry::max_spawn_distance(self.geometry.width, self.geometry.height);
+        let hint = match self.hint_noise {
+            Some(noise) => noise.perturb(dist, max_dist, &mut self.rng),
+            None => dist,
+        };
+        self.tracker.mark_search(
+            &self.player_coordinates,
+            SearchRecord {
+                turn: self.turn,
+                distance: Some(hint).filter(|_| self.distance_hints),
+            },
         );
-        display::print_found_nothing(dist_to_tresure);
+        Some(hint)
+    }
+
+    /// this handles the use of an item, see `use_item_logic`
+    ///
+    /// # Arguments
+    /// * `item` - the item to use
+    ///
+    /// # Returns
+    /// * `bool` - true if the metal detector found the treasure, the game is won
+    fn use_item(&mut self, item: Item) -> bool {
+        match self.use_item_logic(item) {
+            Ok(result) => {
+                display::print_item_result(&result);
+                if result == ItemResult::MetalDetector(true) {
+                    display::print_win_screen(self.seed);
+                    return true;
+                }
+                false
+            }
+            Err(err) => {
+                println!("{}", input::format_error(&err));
+                false
+            }
+        }
+    }
+
+    /// the use of an item itself, without any printing
+    /// the cells the item tells the treasure isn't on are marked on the tracker,
+    /// and the history is updated here
+    ///
+    /// the sonar clears its whole area when it hears nothing, nothing when it hears the treasure
+    /// the compass clears nothing, it only gives the direction
+    /// the metal detector clears its area, or finds the treasure in it
+    ///
+    /// # Arguments
+    /// * `item` - the item to use
+    ///
+    /// # Returns
+    /// * `Ok(ItemResult)` - what the item told
+    /// * `Err(BoardError::InvalidCommand)` - if the item has no use left, nothing changed
+    fn use_item_logic(&mut self, item: Item) -> Result<ItemResult, BoardError> {
+        self.inventory.take(item)?;
+        let (result, cleared) = match item {
+            Item::Sonar => {
+                let area = self.area_around_player(Board::SONAR_RADIUS);
+                match area.contains(&self.treasure_coordinates) {
+                    true => (ItemResult::Sonar(true), Vec::new()),
+                    false => (ItemResult::Sonar(false), area),
+                }
+            }
+            Item::Compass => (ItemResult::Compass(self.treasure_direction()), Vec::new()),
+            Item::MetalDetector => {
+                let area = self.area_around_player(Board::METAL_DETECTOR_RADIUS);
+                match area.contains(&self.treasure_coordinates) {
+                    true => {
+                        self.is_treasure_found = true;
+                        (ItemResult::MetalDetector(true), Vec::new())
+                    }
+                    false => (ItemResult::MetalDetector(false), area),
+                }
+            }
+        };
+
+        for position in &cleared {
+            self.tracker.mark_cleared(position, self.turn);
+        }
+        self.history.push(GameEvent::Scan(cleared));
+        Ok(result)
+    }
+
+    /// the direction of the treasure from the player, along the shortest path on the torus
+    /// used by the direction hints and by the compass
+    ///
+    /// the ties are broken the same way every time:
+    /// * when the treasure is exactly half a board away on an axis, both ways around are as short,
+    ///   the path goes right, or up, see `Position::torus_vector`
+    /// * the direction is diagonal unless an axis is more than twice the other,
+    ///   when it's exactly twice the direction is straight, see `Direction::from_vector`
+    ///
+    /// # Returns
+    /// * `Some(Direction)` - one of the 8 directions
+    /// * `None` - if the player is on the treasure
+    fn treasure_direction(&self) -> Option<Direction> {
+        Direction::from_vector(
+            self.player_coordinates
+                .torus_vector(&self.treasure_coordinates, &self.geometry),
+        )
+    }
 
-        false
+    /// the cells at most `radius` away from the player, the torus wraps them around
+    /// a cell is only given once, even when the area is wider than the board
+    ///
+    /// # Arguments
+    /// * `radius` - the biggest x or y distance from the player
+    ///
+    /// # Returns
+    /// * `Vec<Position>` - the cells of the area, the player one included
+    fn area_around_player(&self, radius: u32) -> Vec<Position> {
+        let radius = radius as i64;
+        let (x, y) = self.player_coordinates.to_i64();
+        let mut area: Vec<Position> = Vec::new();
+        for dx in -radius..=radius {
+            for dy in -radius..=radius {
+                let position = Board::coordinate_modulo((x + dx, y + dy), &self.geometry);
+                if !area.contains(&position) {
+                    area.push(position);
+                }
+            }
+        }
+        area
     }
 
     /// this is handle the game settings selection
     /// when the settings have been selected, it will generate the
     /// board for the game
     ///
+    /// # Arguments
+    /// * `config` - the configuration file, the menu starts with its startup profile
+    ///
     /// # Returns
     /// * `Board` - the board for the game, with the user submitted settings
-    fn init_game() -> Board {
-        let mut game_settings = GameSettings::get_default_settings();
+    fn init_game(config: &mut Config) -> Board {
+        let mut game_settings = config.starting_settings();
         let mut is_setting_over = false;
 
         display::print_init();
 
         while !is_setting_over {
-            display::print_game_settings(&game_settings);
+            display::print_game_settings(&game_settings, config);
 
             match input::get_choice_setting().as_str() {
                 "0" => game_settings.seed = input::get_seed_setting(),
-                "1" => game_settings.player_color = input::get_color_setting(),
+                "1" => {
+                    let palette = game_settings.palette;
+                    game_settings.player_color = input::get_color_setting(palette);
+                    if let Ok(Some(warning)) =
+                        palette.check_player_color(&game_settings.player_color)
+                    {
+                        display::print_color_warning(&warning);
+                    }
+                }
                 "2" => game_settings.player_tile = input::get_tile_setting(),
-                "d" | "default" => game_settings = GameSettings::get_default_settings(),
+                "3" => match input::get_move_set_setting() {
+                    Some(move_set) => game_settings.move_set = move_set,
+                    None => match MoveSet::from_file(&input::get_move_set_path()) {
+                        Ok(move_set) => game_settings.move_set = move_set,
+                        Err(err) => println!("The move set could not be loaded, {}.", err),
+                    },
+                },
+                "4" => {
+                    let (width, height) = input::get_board_size_setting();
+                    let geometry = game_settings.geometry;
+                    match Geometry::new(width, height, geometry.reach, geometry.spawn_distance) {
+                        Ok(geometry) => game_settings.geometry = geometry,
+                        Err(err) => println!("The board size was not changed, {}.", err),
+                    }
+                }
+                "5" => {
+                    let geometry = game_settings.geometry;
+                    let reach = input::get_reach_setting();
+                    match Geometry::new(
+                        geometry.width,
+                        geometry.height,
+                        reach,
+                        geometry.spawn_distance,
+                    ) {
+                        Ok(geometry) => game_settings.geometry = geometry,
+                        Err(err) => println!("The reach was not changed, {}.", err),
+                    }
+                }
+                "6" => game_settings.distance_hints = !game_settings.distance_hints,
+                "7" => game_settings.zmoves_allowed = !game_settings.zmoves_allowed,
+                "8" => {
+                    let geometry = game_settings.geometry;
+                    let spawn_distance = input::get_spawn_distance_setting();
+                    match Geometry::new(
+                        geometry.width,
+                        geometry.height,
+                        geometry.reach,
+                        spawn_distance,
+                    ) {
+                        Ok(geometry) => game_settings.geometry = geometry,
+                        Err(err) => println!("The spawn distance was not changed, {}.", err),
+                    }
+                }
+                "9" => game_settings.fog_radius = input::get_fog_radius_setting(),
+                "10" => game_settings.direction_hints = !game_settings.direction_hints,
+                "11" => game_settings.hint_noise = input::get_hint_noise_setting(),
+                "12" => {
+                    // the old color may be lost on the new water
+                    game_settings.palette = input::get_palette_setting();
+                    game_settings.player_color = game_settings.palette.player_color();
+                    display::print_palette_color(&game_settings.player_color);
+                }
+                "p" | "profile" => {
+                    let name = input::get_profile_name();
+                    match config.profile_settings(&name) {
+                        Some(settings) => game_settings = settings,
+                        None => println!("There is no profile named '{}'.", name),
+                    }
+                }
+                "s" | "save" => {
+                    Board::save_profile(config, input::get_profile_name(), &game_settings)
+                }
+                "g" | "code" => game_settings = input::get_game_code(),
+                "d" | "default" => game_settings = GameSettings::get_new_settings(),
                 _ => is_setting_over = true,
             }
         }
@@ -349,9 +1030,723 @@ impl Board {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use matches::assert_matches;
+
+    /// the odds of the cells in view, see `Board::candidate_weights`
+    fn candidate_weights(board: &Board) -> Vec<(Position, f64)> {
+        match board.candidate_summary() {
+            Some((total, _, _)) => board.candidate_weights(total),
+            None => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn reach_overlay_wraps() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.player_coordinates = Position { x: 0, y: 14 };
+        let reach = test_board.reach_overlay();
+
+        let side = 2 * Board::MOVE_MAX_DISTANCE as usize + 1;
+        assert_eq!(reach.len(), side * side - 1);
+        for (position, tile, _) in &reach {
+            assert_eq!(*tile, Board::REACH_TILE);
+            assert!(Position::is_dist_legal(
+                test_board.player_coordinates.get_xy_dists(position),
+                &test_board.geometry
+            ));
+        }
+        assert!(reach
+            .iter()
+            .any(|(position, _, _)| *position == Position { x: 11, y: 3 }));
+
+        test_board.move_set = MoveSet::Knight;
+        assert_eq!(test_board.reach_overlay().len(), 8);
+    }
+
+    #[test]
+    fn fog_hides_far_cells() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.player_coordinates = Position { x: 0, y: 14 };
+        let visible = |board: &Board| {
+            (0..15)
+                .flat_map(|x| (0..15).map(move |y| Position { x, y }))
+                .filter(|position| board.is_visible(position))
+                .count()
+        };
+        assert_eq!(visible(&test_board), 15 * 15);
+
+        test_board.fog_radius = Some(2);
+        assert_eq!(visible(&test_board), 5 * 5);
+        // the sight wraps around the torus
+        assert!(test_board.is_visible(&Position { x: 13, y: 1 }));
+        assert!(!test_board.is_visible(&Position { x: 3, y: 14 }));
+
+        // the searched cells are remembered
+        test_board.tracker.mark_cleared(&Position { x: 7, y: 7 }, 1);
+        assert!(test_board.is_visible(&Position { x: 7, y: 7 }));
+        assert_eq!(visible(&test_board), 5 * 5 + 1);
+
+        test_board.fog_radius = Some(0);
+        assert_eq!(visible(&test_board), 2);
+    }
+
+    #[test]
+    fn items_clear_the_tracker() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.player_coordinates = Position { x: 0, y: 0 };
+        test_board.treasure_coordinates = Position { x: 10, y: 3 };
+        let searched = |board: &Board| board.tracker.searched_count();
+
+        // far away, the sonar clears its area, wrapped around the torus
+        assert_eq!(
+            test_board.use_item_logic(Item::Sonar).unwrap(),
+            ItemResult::Sonar(false)
+        );
+        let side = 2 * Board::SONAR_RADIUS as usize + 1;
+        assert_eq!(searched(&test_board), side * side);
+        assert!(test_board.tracker.is_searched(&Position { x: 13, y: 14 }));
+        assert_matches!(test_board.history.last(), Some(GameEvent::Scan(cells)) if cells.len() == side * side);
+
+        // in range, nothing is cleared
+        test_board.player_coordinates = Position { x: 7, y: 1 };
+        assert_eq!(
+            test_board.use_item_logic(Item::Sonar).unwrap(),
+            ItemResult::Sonar(true)
+        );
+        assert_eq!(searched(&test_board), side * side);
+        assert_matches!(
+            test_board.use_item_logic(Item::Sonar).unwrap_err(),
+            BoardError::InvalidCommand(_)
+        );
+
+        // the compass goes the short way, 3 right and 2 up
+        assert_eq!(
+            test_board.use_item_logic(Item::Compass).unwrap(),
+            ItemResult::Compass(Some(Direction::UpRight))
+        );
+        test_board.player_coordinates = test_board.treasure_coordinates.clone();
+        assert_eq!(
+            test_board.use_item_logic(Item::Compass).unwrap(),
+            ItemResult::Compass(None)
+        );
+        assert_eq!(searched(&test_board), side * side);
+        assert_eq!(test_board.history.len(), 5);
+    }
+
+    #[test]
+    fn metal_detector_searches_around() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.player_coordinates = Position { x: 14, y: 7 };
+        test_board.treasure_coordinates = Position { x: 0, y: 8 };
+        let mut missed = test_board.clone();
+        missed.treasure_coordinates = Position { x: 1, y: 8 };
+
+        assert_eq!(
+            missed.use_item_logic(Item::MetalDetector).unwrap(),
+            ItemResult::MetalDetector(false)
+        );
+        assert!(missed.tracker.is_searched(&Position { x: 0, y: 8 }));
+        assert!(missed.tracker.is_searched(&Position { x: 13, y: 6 }));
+        assert!(!missed.is_treasure_found);
+
+        assert_eq!(
+            test_board.use_item_logic(Item::MetalDetector).unwrap(),
+            ItemResult::MetalDetector(true)
+        );
+        assert!(test_board.is_treasure_found);
+    }
+
+    #[test]
+    fn exact_hints_narrow_the_candidates() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.treasure_coordinates = Position { x: 7, y: 7 };
+        // every cell is a candidate before any hint
+        assert_eq!(candidate_weights(&test_board).len(), 225);
+
+        test_board.player_coordinates = Position { x: 0, y: 0 };
+        assert_eq!(test_board.search_logic(), Some(7));
+        // the ring 7 cells away, 15 * 15 - 13 * 13
+        let weights = candidate_weights(&test_board);
+        assert_eq!(weights.len(), 56);
+        assert!(weights
+            .iter()
+            .all(|(_, weight)| (weight - 1.0 / 56.0).abs() < 1e-12));
+
+        test_board.player_coordinates = Position { x: 4, y: 4 };
+        assert_eq!(test_board.search_logic(), Some(3));
+        let weights = candidate_weights(&test_board);
+        assert!(weights
+            .iter()
+            .any(|(position, _)| *position == Position { x: 7, y: 7 }));
+        assert!(weights.iter().all(|(position, _)| {
+            let dist = |from: Position| {
+                Position::get_shortest_dist(from.get_xy_dists(position), &test_board.geometry)
+            };
+            dist(Position { x: 0, y: 0 }) == 7 && dist(Position { x: 4, y: 4 }) == 3
+        }));
+
+        // without the distance hints the solver knows nothing more
+        test_board.distance_hints = false;
+        test_board.player_coordinates = Position { x: 1, y: 1 };
+        test_board.search_logic();
+        assert_eq!(test_board.tracker.searches().len(), 3);
+        assert_eq!(test_board.tracker.searches()[2].1.distance, None);
+        assert_eq!(candidate_weights(&test_board), weights);
+    }
+
+    #[test]
+    fn a_wrapping_ring_is_weighed_once() {
+        let mut settings = GameSettings::get_default_settings();
+        settings.geometry = Geometry::new(10, 10, 4, 0).unwrap();
+        let mut test_board = Board::new(settings);
+        test_board.player_coordinates = Position { x: 0, y: 0 };
+        test_board.treasure_coordinates = Position { x: 5, y: 5 };
+
+        // half a board away both ways around are the same cells, 10 * 10 - 9 * 9
+        assert_eq!(test_board.search_logic(), Some(5));
+        let weights = candidate_weights(&test_board);
+        assert_eq!(weights.len(), 19);
+        assert!(weights
+            .iter()
+            .all(|(_, weight)| (weight - 1.0 / 19.0).abs() < 1e-12));
+        assert_eq!(
+            test_board.best_candidate(),
+            Some((Position { x: 0, y: 5 }, 1.0 / 19.0))
+        );
+    }
+
+    #[test]
+    fn candidates_are_weighed_on_the_whole_board() {
+        let mut settings = GameSettings::get_default_settings();
+        settings.geometry = Geometry::new(1000, 1000, 4, 0).unwrap();
+        let mut test_board = Board::new(settings);
+        test_board.player_coordinates = Position { x: 0, y: 0 };
+        test_board.treasure_coordinates = Position { x: 2, y: 0 };
+        assert!(test_board.is_view_cut());
+
+        // the view wraps around the corner, only its cells are given but with the odds of the whole board
+        let weights = candidate_weights(&test_board);
+        let (columns, rows) = test_board.viewport();
+        assert_eq!(weights.len(), columns.len() * rows.len());
+        assert!(weights
+            .iter()
+            .all(|(position, _)| columns.contains(&position.x) && rows.contains(&position.y)));
+        let cells = 1000.0 * 1000.0;
+        assert!(weights
+            .iter()
+            .all(|(_, weight)| (weight - 1.0 / cells).abs() < 1e-15));
+
+        assert_eq!(test_board.search_logic(), Some(2));
+        let best = test_board.best_candidate().unwrap();
+        assert_eq!(best.1, 1.0 / 16.0); // the ring 2 cells away, 5 * 5 - 3 * 3
+
+        // the hint points out of view, the odds shown in it aren't inflated
+        test_board.player_coordinates = Position { x: 500, y: 500 };
+        test_board.pan = (0, 0);
+        assert!(candidate_weights(&test_board).is_empty());
+        assert_eq!(test_board.best_candidate().unwrap().1, 1.0 / 16.0);
+    }
+
+    #[test]
+    fn noisy_hints_are_seeded() {
+        let mut settings = GameSettings::get_default_settings();
+        settings.hint_noise = Some(HintNoise::new(2, 60, 20).unwrap());
+        let hints = |settings: &GameSettings| {
+            let mut test_board = Board::new(settings.clone());
+            test_board.treasure_coordinates = Position { x: 7, y: 7 };
+            (0..20)
+                .map(|x| {
+                    test_board.player_coordinates = Position { x: x % 15, y: 1 };
+                    test_board.search_logic().unwrap()
+                })
+                .collect::<Vec<u32>>()
+        };
+
+        let noisy = hints(&settings);
+        assert_eq!(noisy, hints(&settings));
+        assert!(noisy.iter().all(|hint| (1..=7).contains(hint)));
+        let exact = hints(&GameSettings::get_default_settings());
+        assert_ne!(noisy, exact);
+        settings.seed += 1;
+        assert_ne!(noisy, hints(&settings));
+    }
+
+    #[test]
+    fn noisy_hints_add_up() {
+        // no lies, so the treasure is never ruled out, and each hint makes it stand out a bit more
+        let mut settings = GameSettings::get_default_settings();
+        settings.hint_noise = Some(HintNoise::new(1, 50, 0).unwrap());
+        let mut test_board = Board::new(settings);
+        test_board.treasure_coordinates = Position { x: 9, y: 4 };
+        let treasure_odds = |board: &Board| {
+            candidate_weights(board)
+                .into_iter()
+                .find(|(position, _)| *position == board.treasure_coordinates)
+                .map(|(_, weight)| weight)
+        };
+
+        let mut odds: Vec<f64> = Vec::new();
+        for (x, y) in [(0, 0), (5, 12), (12, 9), (3, 6), (14, 2), (7, 1)] {
+            test_board.player_coordinates = Position { x, y };
+            test_board.search_logic();
+            odds.push(treasure_odds(&test_board).unwrap());
+        }
+        assert!(odds[5] > odds[0] * 4.0, "{:?}", odds);
+        assert_eq!(
+            test_board.best_candidate().map(|(position, _)| position),
+            Some(Position { x: 9, y: 4 })
+        );
+    }
+
+    #[test]
+    fn heatmap_colors_the_searches() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.treasure_coordinates = Position { x: 7, y: 7 };
+        for (x, y) in [(0, 0), (5, 5)] {
+            test_board.turn += 1;
+            test_board.player_coordinates = Position { x, y };
+            test_board.search_logic();
+        }
+        assert_eq!(
+            test_board.tracker.searches()[1],
+            (
+                Position { x: 5, y: 5 },
+                SearchRecord {
+                    turn: 2,
+                    distance: Some(2)
+                }
+            )
+        );
+        let colors = |board: &Board| {
+            board
+                .heatmap_overlay()
+                .into_iter()
+                .map(|(position, _, color)| (position.x, position.y, color))
+                .collect::<Vec<(u32, u32, Color)>>()
+        };
+
+        assert!(colors(&test_board).is_empty());
+        // the player cell is never colored
+        test_board.heatmap = Heatmap::Distance;
+        assert_eq!(colors(&test_board), vec![(0, 0, Color::Blue)]);
+
+        test_board.turn += 1;
+        test_board.player_coordinates = Position { x: 10, y: 10 };
+        assert_eq!(
+            colors(&test_board),
+            vec![(0, 0, Color::Blue), (5, 5, Color::Red)]
+        );
+        test_board.heatmap = Heatmap::Recency;
+        assert_eq!(
+            colors(&test_board),
+            vec![(0, 0, Color::Blue), (5, 5, Color::Green)]
+        );
+    }
+
+    #[test]
+    fn treasure_direction_ties() {
+        let mut settings = GameSettings::get_default_settings();
+        settings.geometry = Geometry::new(10, 8, 2, 2).unwrap();
+        let mut test_board = Board::new(settings);
+        let direction = |board: &mut Board, player: (u32, u32), treasure: (u32, u32)| {
+            board.player_coordinates = Position {
+                x: player.0,
+                y: player.1,
+            };
+            board.treasure_coordinates = Position {
+                x: treasure.0,
+                y: treasure.1,
+            };
+            board.treasure_direction()
+        };
+
+        // the short way around the torus
+        assert_eq!(
+            direction(&mut test_board, (1, 1), (9, 1)),
+            Some(Direction::Left)
+        );
+        assert_eq!(
+            direction(&mut test_board, (8, 6), (0, 0)),
+            Some(Direction::UpRight)
+        );
+        // half a board away, both ways are as short, it goes right or up
+        assert_eq!(
+            direction(&mut test_board, (7, 2), (2, 2)),
+            Some(Direction::Right)
+        );
+        assert_eq!(
+            direction(&mut test_board, (7, 6), (7, 2)),
+            Some(Direction::Up)
+        );
+        assert_eq!(
+            direction(&mut test_board, (0, 0), (5, 4)),
+            Some(Direction::UpRight)
+        );
+        // exactly twice on an axis is straight, a bit less is diagonal
+        assert_eq!(
+            direction(&mut test_board, (0, 0), (4, 6)),
+            Some(Direction::Right)
+        );
+        assert_eq!(
+            direction(&mut test_board, (0, 0), (7, 6)),
+            Some(Direction::DownLeft)
+        );
+        assert_eq!(direction(&mut test_board, (3, 3), (3, 3)), None);
+    }
+
+    #[test]
+    fn zmove_rays_overlay_shows_speeds() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.player_coordinates = Position { x: 0, y: 0 };
+        let rays = test_board.zmove_rays_overlay();
+
+        assert_eq!(rays.len(), 8 * Board::MOVE_MAX_DISTANCE as usize);
+        assert!(rays.contains(&(Position { x: 0, y: 3 }, '3', test_board.player_color)));
+        assert!(rays.contains(&(Position { x: 13, y: 13 }, '2', test_board.player_color)));
+
+        test_board.move_set = MoveSet::King;
+        assert!(test_board
+            .zmove_rays_overlay()
+            .iter()
+            .all(|(_, tile, _)| *tile == '1'));
+    }
+
+    #[test]
+    fn sequence_path_wraps() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.player_coordinates = Position { x: 13, y: 1 };
+        let zmoves = vec![Zmove::new(6, 2).unwrap(), Zmove::new(2, 2).unwrap()];
+
+        assert_eq!(
+            test_board.sequence_path(&zmoves),
+            vec![
+                Position { x: 14, y: 1 },
+                Position { x: 0, y: 1 },
+                Position { x: 0, y: 0 },
+                Position { x: 0, y: 14 },
+            ]
+        );
+    }
+
+    /// a board of the biggest size, the player in a corner so the viewport wraps across the edges
+    fn biggest_board() -> Board {
+        let mut settings = GameSettings::get_default_settings();
+        settings.geometry =
+            Geometry::new(Board::MAX_BOARD_SIDE, Board::MAX_BOARD_SIDE, 4, 2).unwrap();
+        let mut test_board = Board::new(settings);
+        test_board.player_coordinates = Position {
+            x: 3,
+            y: Board::MAX_BOARD_SIDE - 2,
+        };
+        test_board
+    }
+
+    #[test]
+    fn biggest_boards_are_drawn_around_the_player() {
+        let mut test_board = biggest_board();
+        assert!(!test_board.search_player_position());
+        test_board.show_reach = true;
+
+        // the player is in the middle, the view goes on across the torus edges
+        let (columns, rows) = test_board.viewport();
+        assert_eq!(columns.len(), Board::VIEWPORT_WIDTH as usize);
+        assert_eq!(columns[0], Board::MAX_BOARD_SIDE - 17);
+        assert_eq!(columns[Board::VIEWPORT_WIDTH as usize / 2], 3);
+        assert_eq!(rows[0], Board::MAX_BOARD_SIDE - 17);
+        assert_eq!(rows[Board::VIEWPORT_HEIGHT as usize - 1], 12);
+        // so the reach is whole, the cells across the edges too
+        assert_eq!(test_board.reach_overlay().len(), 9 * 9 - 1);
+
+        let mut buffer = termcolor::Buffer::no_color();
+        test_board
+            .write_game_board(&mut buffer, &test_board.reach_overlay())
+            .unwrap();
+        let text = String::from_utf8(buffer.into_inner()).unwrap();
+        // the borders, the rows, the coordinates, then the minimap and its title
+        assert_eq!(
+            text.lines().count(),
+            Board::VIEWPORT_HEIGHT as usize + 3 + Board::MINIMAP_HEIGHT as usize + 1
+        );
+        // the labels are the true coordinates
+        assert!(text.contains(&format!("{} ∣", Board::MAX_BOARD_SIDE - 1)));
+        assert!(text.contains("12 ∣"));
+        assert!(!text.contains("13 ∣"));
+
+        // a small board is drawn whole, without a minimap
+        let test_board = Board::new(GameSettings::get_default_settings());
+        assert_eq!(
+            test_board.viewport(),
+            ((0..15).collect(), (0..15).collect())
+        );
+        let mut buffer = termcolor::Buffer::no_color();
+        test_board.write_game_board(&mut buffer, &[]).unwrap();
+        let text = String::from_utf8(buffer.into_inner()).unwrap();
+        assert!(!text.contains("minimap"));
+    }
+
+    /// the lines of a board drawn without colors
+    fn rendered(test_board: &Board) -> Vec<String> {
+        let mut buffer = termcolor::Buffer::no_color();
+        test_board.write_game_board(&mut buffer, &[]).unwrap();
+        let text = String::from_utf8(buffer.into_inner()).unwrap();
+        text.lines().map(String::from).collect()
+    }
+
+    /// a board of the given size drawn in a terminal, and the lines of its frame
+    fn drawn_in(width: u32, height: u32, terminal: Option<(u32, u32)>) -> (Board, Vec<String>) {
+        let mut settings = GameSettings::get_default_settings();
+        settings.geometry = Geometry::new(width, height, 2, 2).unwrap();
+        let mut test_board = Board::new(settings);
+        test_board.terminal = terminal;
+        let lines = rendered(&test_board);
+        (test_board, lines)
+    }
+
+    #[test]
+    fn cells_narrow_to_fit_the_terminal() {
+        let terminal = Some((80, 40));
+        // 3 columns a cell, the coordinates of every column
+        let (test_board, lines) = drawn_in(15, 15, terminal);
+        assert_eq!(test_board.layout(), (3, 15, 15));
+        assert_eq!(lines[1], format!("14 ∣{}∣", " ~ ".repeat(15)));
+        assert!(lines[17].starts_with("     0  1  2 "));
+
+        // 2 columns, a coordinate every 2 columns
+        let (test_board, lines) = drawn_in(30, 20, terminal);
+        assert_eq!(test_board.layout(), (2, 30, 20));
+        assert_eq!(lines[0], format!("   ⌜{}⌝", "⎺".repeat(60)));
+        assert_eq!(lines[1], format!("19 ∣{}∣", "~ ".repeat(30)));
+        assert!(lines[22].starts_with("    0   2   4 "));
+
+        // 1 column, a coordinate every 3 columns
+        let (test_board, lines) = drawn_in(70, 20, terminal);
+        assert_eq!(test_board.layout(), (1, 70, 20));
+        assert_eq!(lines[1], format!("19 ∣{}∣", "~".repeat(70)));
+        assert!(lines[22].starts_with("    0  3  6  9  12 15 "));
+        assert!(lines.iter().all(|line| line.chars().count() <= 80));
+
+        // without a terminal it's the usual view
+        let (test_board, _) = drawn_in(70, 20, None);
+        assert_eq!(
+            test_board.layout(),
+            (3, Board::VIEWPORT_WIDTH, Board::VIEWPORT_HEIGHT)
+        );
+    }
+
+    #[test]
+    fn boards_too_big_fall_back_to_the_view() {
+        let (mut test_board, _) = drawn_in(200, 150, Some((80, 40)));
+        test_board.player_coordinates = Position { x: 0, y: 0 };
+        let lines = rendered(&test_board);
+        // the labels take 3 digits, the view is as wide as the terminal
+        let rows = 40 - Board::BOARD_FRAME_LINES - Board::MINIMAP_HEIGHT - 1;
+        assert_eq!(test_board.layout(), (3, 24, rows));
+        let frame = &lines[..rows as usize + 2];
+        assert!(frame
+            .iter()
+            .all(|line| line.chars().count() == 3 + 2 + 24 * 3 + 1));
+        // the view wraps, the labels are the true coordinates, right aligned
+        assert!(lines[1].starts_with(" 12 ∣"));
+        assert!(lines[rows as usize].starts_with("137 ∣"));
+        // the 3 digits coordinates don't touch, there is one every 2 columns
+        assert!(lines[rows as usize + 2].starts_with("     188   190   192 "));
+        assert!(lines[rows as usize + 3].contains("minimap"));
+
+        // the pan moves half the view
+        assert!(test_board.pan_view(Some(Direction::Right)));
+        assert_eq!(test_board.view_center(), Position { x: 12, y: 0 });
+    }
+
+    #[test]
+    fn wide_tiles_keep_the_grid_aligned() {
+        use unicode_width::UnicodeWidthStr;
+
+        for tile in ["🦀", "宝", "👍🏽", "e\u{301}"] {
+            let mut settings = GameSettings::get_default_settings();
+            settings.geometry = Geometry::new(15, 10, 2, 2).unwrap();
+            settings.player_tile = Tile::from_str(tile).unwrap();
+            let mut test_board = Board::new(settings);
+            test_board.player_coordinates = Position { x: 4, y: 9 };
+
+            // without a terminal the cells are 3 columns, 2 to fit in a small one
+            for (terminal, cell_width) in [(None, 3), (Some((40, 20)), 2)] {
+                test_board.terminal = terminal;
+                assert_eq!(test_board.layout().0, cell_width, "{}", tile);
+                let lines = rendered(&test_board);
+                // the row of the player is as wide as the borders
+                let frame_width = lines[0].width();
+                assert_eq!(lines[1].width(), frame_width, "{}", tile);
+                assert_eq!(lines[2].width(), frame_width);
+                assert_eq!(lines[11].width(), frame_width);
+                // the cluster is drawn whole, after the 4 cells on its left
+                let start = lines[1].find(tile).unwrap();
+                let padding = (cell_width - tile.width()) / 2;
+                assert_eq!(lines[1][..start].width(), 4 + 4 * cell_width + padding);
+            }
+        }
+
+        // a tile can't be narrower than the cells, so the board is cut when it doesn't fit
+        let mut settings = GameSettings::get_default_settings();
+        settings.geometry = Geometry::new(70, 20, 2, 2).unwrap();
+        settings.player_tile = Tile::from('🦀');
+        let mut test_board = Board::new(settings);
+        test_board.terminal = Some((80, 40));
+        assert_eq!(test_board.layout(), (3, 25, 20));
+    }
+
+    #[test]
+    fn views_pan_and_follow_the_player() {
+        let mut test_board = biggest_board();
+        assert!(test_board.pan_view(Some(Direction::UpRight)));
+        assert!(test_board.pan_view(Some(Direction::Right)));
+        assert_eq!(
+            test_board.view_center(),
+            Position {
+                x: 3 + Board::VIEWPORT_WIDTH,
+                y: Board::VIEWPORT_HEIGHT / 2 - 2
+            }
+        );
+        // the view keeps its offset as the player moves
+        test_board.player_coordinates = Position { x: 5, y: 0 };
+        assert_eq!(
+            test_board.view_center(),
+            Position {
+                x: 5 + Board::VIEWPORT_WIDTH,
+                y: Board::VIEWPORT_HEIGHT / 2
+            }
+        );
+        assert!(test_board.pan_view(None));
+        assert_eq!(test_board.view_center(), Position { x: 5, y: 0 });
+
+        // there is nothing to pan on a small board
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        assert!(!test_board.pan_view(Some(Direction::Up)));
+        assert_eq!(test_board.view_center(), test_board.player_coordinates);
+    }
+
+    #[test]
+    fn minimaps_summarize_the_known_cells() {
+        let mut settings = GameSettings::get_default_settings();
+        settings.geometry = Geometry::new(80, 40, 4, 2).unwrap();
+        let mut test_board = Board::new(settings);
+        test_board.player_coordinates = Position { x: 0, y: 0 };
+        test_board.treasure_coordinates = Position { x: 40, y: 20 };
+        // blocks of 2x4 cells, the top right one fully known, one half known and one barely
+        for x in 78..80 {
+            for y in 36..40 {
+                test_board.tracker.mark_cleared(&Position { x, y }, 0);
+            }
+        }
+        for (x, y) in [(10, 4), (11, 4), (10, 5), (11, 5), (20, 8)] {
+            test_board.tracker.mark_cleared(&Position { x, y }, 0);
+        }
+
+        let (columns, rows) = test_board.viewport();
+        let mut buffer = termcolor::Buffer::no_color();
+        test_board
+            .write_minimap(&mut buffer, &columns, &rows)
+            .unwrap();
+        let text = String::from_utf8(buffer.into_inner()).unwrap();
+        let lines: Vec<&str> = text.lines().map(str::trim).collect();
+        assert_eq!(lines.len(), Board::MINIMAP_HEIGHT as usize + 1);
+        assert!(lines[0].starts_with("minimap, a char is 2x4 cells"));
+        // the top row first, the player block is at the bottom left
+        assert_eq!(lines[1], format!("{}#", ".".repeat(39)));
+        assert_eq!(lines[9], format!("{}+{}", ".".repeat(5), ".".repeat(34)));
+        assert_eq!(lines[8], format!("{}-{}", ".".repeat(10), ".".repeat(29)));
+        assert_eq!(lines[10], format!("@{}", ".".repeat(39)));
+    }
+
+    /// the mean time of an action, in nanoseconds
+    fn mean_nanos<F: FnMut()>(runs: u32, mut action: F) -> u128 {
+        let start = std::time::Instant::now();
+        for _ in 0..runs {
+            action();
+        }
+        start.elapsed().as_nanos() / runs as u128
+    }
+
+    // run it with `cargo test --release -- --ignored --nocapture costs_stay_constant`
+    #[test]
+    #[ignore]
+    fn costs_stay_constant() {
+        let mut timings: Vec<(u32, [u128; 3])> = Vec::new();
+        for side in [100, 1000, Board::MAX_BOARD_SIDE] {
+            let mut settings = GameSettings::get_default_settings();
+            settings.geometry = Geometry::new(side, side, 4, 2).unwrap();
+            let mut test_board = Board::new(settings);
+            test_board.treasure_coordinates = Position { x: 0, y: 0 };
+            test_board.player_coordinates = Position { x: 50, y: 50 };
+
+            let search = mean_nanos(1000, || {
+                test_board.search_logic();
+            });
+            let mut x = 50;
+            let moves = mean_nanos(1000, || {
+                x = 102 - x; // back and forth between 50 and 52
+                let _ = test_board.teleport_logic(&Position { x, y: 50 });
+            });
+            let render = mean_nanos(200, || {
+                let mut buffer = termcolor::Buffer::no_color();
+                test_board.write_game_board(&mut buffer, &[]).unwrap();
+            });
+            println!(
+                "{0}x{0}: search {1}ns, move {2}ns, render {3}ns",
+                side, search, moves, render
+            );
+            timings.push((side, [search, moves, render]));
+        }
+
+        // a hundred times more cells on a side, the same costs, give or take the noise
+        let (_, small) = timings[0];
+        let (_, big) = timings[2];
+        for (small, big) in small.iter().zip(big.iter()) {
+            assert!(*big < 4 * small + 10_000, "{:?}", timings);
+        }
+    }
+
+    #[test]
+    fn geometry_follows_the_settings() {
+        let mut game_settings = GameSettings::get_default_settings();
+        game_settings.geometry = Geometry::new(30, 20, 2, 2).unwrap();
+        let mut test_board = Board::new(game_settings);
+        // the corner is tracked
+        assert!(!test_board.tracker.is_searched(&Position { x: 29, y: 19 }));
+
+        test_board.player_coordinates = Position { x: 0, y: 0 };
+        assert_eq!(test_board.reach_overlay().len(), 24);
+        assert!(test_board
+            .teleport_logic(&Position { x: 28, y: 18 })
+            .is_ok());
+        assert_matches!(
+            test_board.teleport_logic(&Position { x: 1, y: 18 }),
+            Err(BoardError::InvalidMove(_))
+        );
+    }
+
+    #[test]
+    fn rules_turn_off_zmoves() {
+        let mut game_settings = GameSettings::get_default_settings();
+        game_settings.zmoves_allowed = false;
+        let test_board = Board::new(game_settings);
+
+        assert_matches!(
+            test_board.check_rules(&Command::AskZmove),
+            Err(BoardError::InvalidCommand(_))
+        );
+        assert_matches!(
+            test_board.check_rules(&Command::from_str("8,2 6,3 s").unwrap()),
+            Err(BoardError::InvalidCommand(_))
+        );
+        assert!(test_board.check_rules(&Command::AskTeleport).is_ok());
+        assert!(Board::new(GameSettings::get_default_settings())
+            .check_rules(&Command::AskZmove)
+            .is_ok());
+    }
 
     #[test]
     fn valid_position_in_board() {
+        let geometry = Geometry::default();
         let bottom_left = Position { x: 0, y: 0 };
         let bottom_right = Position {
             x: Board::DEFAULT_BOARD_WIDTH - 1,
@@ -371,15 +1766,16 @@ mod tests {
         };
 
         //verify if the positions are in board
-        assert!(Board::is_in_board(&bottom_left));
-        assert!(Board::is_in_board(&bottom_right));
-        assert!(Board::is_in_board(&top_left));
-        assert!(Board::is_in_board(&top_right));
-        assert!(Board::is_in_board(&somewhere_inside));
+        assert!(Board::is_in_board(&bottom_left, &geometry));
+        assert!(Board::is_in_board(&bottom_right, &geometry));
+        assert!(Board::is_in_board(&top_left, &geometry));
+        assert!(Board::is_in_board(&top_right, &geometry));
+        assert!(Board::is_in_board(&somewhere_inside, &geometry));
     }
 
     #[test]
     fn invalid_position_in_board() {
+        let geometry = Geometry::default();
         // as we verify positions, there are no negative value, the only quadrant verified is the first one
 
         let bottom_right_and_one_right = Position {
@@ -431,21 +1827,25 @@ mod tests {
         let oob_up_max = Position { x: 0, y: u32::MAX };
         let oob_right_max = Position { x: u32::MAX, y: 0 };
 
-        assert!(!Board::is_in_board(&bottom_right_and_one_right));
-        assert!(!Board::is_in_board(&top_left_and_one_up));
-        assert!(!Board::is_in_board(&top_right_and_one_up));
-        assert!(!Board::is_in_board(&top_right_and_one_right));
-        assert!(!Board::is_in_board(&top_right_and_one_diagonal_out));
-        assert!(!Board::is_in_board(&oob_diagonal));
-        assert!(!Board::is_in_board(&oob_up));
-        assert!(!Board::is_in_board(&oob_right));
-        assert!(!Board::is_in_board(&oob_diagonal_max));
-        assert!(!Board::is_in_board(&oob_up_max));
-        assert!(!Board::is_in_board(&oob_right_max));
+        assert!(!Board::is_in_board(&bottom_right_and_one_right, &geometry));
+        assert!(!Board::is_in_board(&top_left_and_one_up, &geometry));
+        assert!(!Board::is_in_board(&top_right_and_one_up, &geometry));
+        assert!(!Board::is_in_board(&top_right_and_one_right, &geometry));
+        assert!(!Board::is_in_board(
+            &top_right_and_one_diagonal_out,
+            &geometry
+        ));
+        assert!(!Board::is_in_board(&oob_diagonal, &geometry));
+        assert!(!Board::is_in_board(&oob_up, &geometry));
+        assert!(!Board::is_in_board(&oob_right, &geometry));
+        assert!(!Board::is_in_board(&oob_diagonal_max, &geometry));
+        assert!(!Board::is_in_board(&oob_up_max, &geometry));
+        assert!(!Board::is_in_board(&oob_right_max, &geometry));
     }
 
     #[test]
     fn coordinate_modulo_in_board() {
+        let geometry = Geometry::default();
         // If I put them in a vec it will be faster, but then they wouldn't be named
         let bottom_left = Position { x: 0, y: 0 };
         let bottom_right = Position {
@@ -466,21 +1866,31 @@ mod tests {
         };
 
         // verify if modulator works for in board positions
-        assert_eq!(Board::coordinate_modulo(bottom_left.to_i64()), bottom_left);
         assert_eq!(
-            Board::coordinate_modulo(bottom_right.to_i64()),
+            Board::coordinate_modulo(bottom_left.to_i64(), &geometry),
+            bottom_left
+        );
+        assert_eq!(
+            Board::coordinate_modulo(bottom_right.to_i64(), &geometry),
             bottom_right
         );
-        assert_eq!(Board::coordinate_modulo(top_left.to_i64()), top_left);
-        assert_eq!(Board::coordinate_modulo(top_right.to_i64()), top_right);
         assert_eq!(
-            Board::coordinate_modulo(somewhere_inside.to_i64()),
+            Board::coordinate_modulo(top_left.to_i64(), &geometry),
+            top_left
+        );
+        assert_eq!(
+            Board::coordinate_modulo(top_right.to_i64(), &geometry),
+            top_right
+        );
+        assert_eq!(
+            Board::coordinate_modulo(somewhere_inside.to_i64(), &geometry),
             somewhere_inside
         );
     }
 
     #[test]
     fn coordinate_modulator_out_of_board() {
+        let geometry = Geometry::default();
         let bottom_left_and_one_down: (i64, i64) = (0, -1);
         let bottom_left_and_one_left: (i64, i64) = (-1, 0);
         let bottom_left_and_diagonal_out: (i64, i64) = (-1, -1);
@@ -548,66 +1958,105 @@ mod tests {
         };
 
         assert_eq!(
-            Board::coordinate_modulo(bottom_left_and_one_left),
+            Board::coordinate_modulo(bottom_left_and_one_left, &geometry),
             bottom_right
         );
-        assert_eq!(Board::coordinate_modulo(bottom_left_and_one_down), top_left);
         assert_eq!(
-            Board::coordinate_modulo(bottom_left_and_diagonal_out),
+            Board::coordinate_modulo(bottom_left_and_one_down, &geometry),
+            top_left
+        );
+        assert_eq!(
+            Board::coordinate_modulo(bottom_left_and_diagonal_out, &geometry),
             top_right
         );
 
         assert_eq!(
-            Board::coordinate_modulo(bottom_right_and_one_right),
+            Board::coordinate_modulo(bottom_right_and_one_right, &geometry),
             bottom_left
         );
         assert_eq!(
-            Board::coordinate_modulo(bottom_right_and_one_down),
+            Board::coordinate_modulo(bottom_right_and_one_down, &geometry),
             top_right
         );
         assert_eq!(
-            Board::coordinate_modulo(bottom_right_and_diagonal_out),
+            Board::coordinate_modulo(bottom_right_and_diagonal_out, &geometry),
             top_left
         );
 
-        assert_eq!(Board::coordinate_modulo(top_left_and_one_left), top_right);
-        assert_eq!(Board::coordinate_modulo(top_left_and_one_up), bottom_left);
         assert_eq!(
-            Board::coordinate_modulo(top_left_and_diagonal_out),
+            Board::coordinate_modulo(top_left_and_one_left, &geometry),
+            top_right
+        );
+        assert_eq!(
+            Board::coordinate_modulo(top_left_and_one_up, &geometry),
+            bottom_left
+        );
+        assert_eq!(
+            Board::coordinate_modulo(top_left_and_diagonal_out, &geometry),
             bottom_right
         );
 
-        assert_eq!(Board::coordinate_modulo(top_right_and_one_up), bottom_right);
-        assert_eq!(Board::coordinate_modulo(top_right_and_one_right), top_left);
         assert_eq!(
-            Board::coordinate_modulo(top_right_and_one_diagonal_out),
+            Board::coordinate_modulo(top_right_and_one_up, &geometry),
+            bottom_right
+        );
+        assert_eq!(
+            Board::coordinate_modulo(top_right_and_one_right, &geometry),
+            top_left
+        );
+        assert_eq!(
+            Board::coordinate_modulo(top_right_and_one_diagonal_out, &geometry),
             bottom_left
         );
 
-        assert_eq!(Board::coordinate_modulo(oob_quadrant_1), bottom_left);
-        assert_eq!(Board::coordinate_modulo(oob_quadrant_2), bottom_left);
-        assert_eq!(Board::coordinate_modulo(oob_quadrant_3), bottom_left);
-        assert_eq!(Board::coordinate_modulo(oob_quadrant_4), bottom_left);
+        assert_eq!(
+            Board::coordinate_modulo(oob_quadrant_1, &geometry),
+            bottom_left
+        );
+        assert_eq!(
+            Board::coordinate_modulo(oob_quadrant_2, &geometry),
+            bottom_left
+        );
+        assert_eq!(
+            Board::coordinate_modulo(oob_quadrant_3, &geometry),
+            bottom_left
+        );
+        assert_eq!(
+            Board::coordinate_modulo(oob_quadrant_4, &geometry),
+            bottom_left
+        );
 
         // a Position has 2 u32, therefore always >0
         // contrary to the others, I dont know where exactly those will land, so I just check if they're in board
         assert!(
-            Board::is_in_board(&Board::coordinate_modulo(oob_quadrant_1_max)),
+            Board::is_in_board(
+                &Board::coordinate_modulo(oob_quadrant_1_max, &geometry),
+                &geometry
+            ),
             "should be in board"
         );
 
         assert!(
-            Board::is_in_board(&Board::coordinate_modulo(oob_quadrant_2_min_max)),
+            Board::is_in_board(
+                &Board::coordinate_modulo(oob_quadrant_2_min_max, &geometry),
+                &geometry
+            ),
             "should be in board"
         );
 
         assert!(
-            Board::is_in_board(&Board::coordinate_modulo(oob_quadrant_3_min)),
+            Board::is_in_board(
+                &Board::coordinate_modulo(oob_quadrant_3_min, &geometry),
+                &geometry
+            ),
             "should be in board"
         );
 
         assert!(
-            Board::is_in_board(&Board::coordinate_modulo(oob_quadrant_4_max_min)),
+            Board::is_in_board(
+                &Board::coordinate_modulo(oob_quadrant_4_max_min, &geometry),
+                &geometry
+            ),
             "should be in board"
         );
     }
@@ -617,11 +2066,137 @@ mod tests {
         let test_seed: u64 = 12;
         // I dont test if it's in board here, as random_cooridinates calls coordinate_modulo, which is tested in another test
         assert_eq!(
-            Board::random_coordinates(&mut StdRng::seed_from_u64(test_seed)),
-            Board::random_coordinates(&mut StdRng::seed_from_u64(test_seed))
+            Board::random_coordinates(
+                &mut Xoshiro256StarStar::seed_from_u64(test_seed),
+                &Geometry::default()
+            ),
+            Board::random_coordinates(
+                &mut Xoshiro256StarStar::seed_from_u64(test_seed),
+                &Geometry::default()
+            )
+        );
+    }
+
+    #[test]
+    /// golden values, a seed must give the same board in every version of the game
+    /// if this test fails, the shared seeds and game codes are broken
+    fn seeds_give_the_same_boards() {
+        let placements = |geometry: Geometry| {
+            [0, 1, 2, 42, u64::MAX]
+                .iter()
+                .map(|&seed| {
+                    let board = Board::new(GameSettings {
+                        seed,
+                        geometry,
+                        ..GameSettings::get_default_settings()
+                    });
+                    (
+                        board.player_coordinates.clone(),
+                        board.treasure_coordinates.clone(),
+                    )
+                })
+                .collect::<Vec<(Position, Position)>>()
+        };
+        let pos = |x, y| Position { x, y };
+
+        assert_eq!(
+            placements(Geometry::default()),
+            vec![
+                (pos(13, 2), pos(5, 0)),
+                (pos(0, 7), pos(1, 12)),
+                (pos(4, 11), pos(2, 2)),
+                (pos(3, 2), pos(0, 4)),
+                // the first treasure was right next to the player
+                (pos(8, 3), pos(13, 1)),
+            ]
+        );
+        assert_eq!(
+            placements(Geometry::new(40, 9, 3, 0).unwrap()),
+            vec![
+                (pos(38, 8), pos(5, 6)),
+                (pos(5, 1), pos(6, 0)),
+                (pos(29, 2), pos(7, 2)),
+                (pos(38, 2), pos(10, 1)),
+                (pos(13, 3), pos(32, 4)),
+            ]
         );
     }
 
+    /// the chi-squared statistic of counts that should all be equal
+    fn chi_squared(counts: &[u32]) -> f64 {
+        let expected = counts.iter().sum::<u32>() as f64 / counts.len() as f64;
+        counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    /// the value a chi-squared statistic only goes over 0.1% of the time,
+    /// with the Wilson-Hilferty approximation, close enough from 10 degrees of freedom
+    fn chi_squared_threshold(degrees_of_freedom: usize) -> f64 {
+        let k = degrees_of_freedom as f64;
+        let z = 3.09; // the 99.9% quantile of the normal law
+        k * (1.0 - 2.0 / (9.0 * k) + z * (2.0 / (9.0 * k)).sqrt()).powi(3)
+    }
+
+    #[test]
+    fn spawns_are_uniform() {
+        // 7 is not a power of two, the old modulo would favour the first columns
+        let geometry = Geometry::new(7, 5, 2, 0).unwrap();
+        let cells = (geometry.width * geometry.height) as usize;
+        let index = |pos: &Position| (pos.y * geometry.width + pos.x) as usize;
+        let mut players = vec![0u32; cells];
+        let mut treasures = vec![0u32; cells];
+        for seed in 0..35_000 {
+            let (player, treasure) =
+                Board::spawn_coordinates(&mut Xoshiro256StarStar::seed_from_u64(seed), &geometry);
+            players[index(&player)] += 1;
+            treasures[index(&treasure)] += 1;
+        }
+
+        assert!(chi_squared(&players) < chi_squared_threshold(cells - 1));
+        assert!(chi_squared(&treasures) < chi_squared_threshold(cells - 1));
+    }
+
+    #[test]
+    fn treasures_spawn_far_enough() {
+        let geometry = Geometry::new(9, 7, 2, 3).unwrap();
+        // every offset from the player far enough should have the same odds
+        let offset = |player: &Position, treasure: &Position| {
+            let x = (treasure.x + geometry.width - player.x) % geometry.width;
+            let y = (treasure.y + geometry.height - player.y) % geometry.height;
+            (y * geometry.width + x) as usize
+        };
+        let mut offsets = vec![0u32; (geometry.width * geometry.height) as usize];
+        for seed in 0..30_000 {
+            let (player, treasure) =
+                Board::spawn_coordinates(&mut Xoshiro256StarStar::seed_from_u64(seed), &geometry);
+            let dist = Position::get_shortest_dist(player.get_xy_dists(&treasure), &geometry);
+            assert!(
+                dist >= 3,
+                "seed {} spawned the treasure {} away",
+                seed,
+                dist
+            );
+            offsets[offset(&player, &treasure)] += 1;
+        }
+
+        let reached: Vec<u32> = offsets.into_iter().filter(|&count| count > 0).collect();
+        // the offsets at a distance of 3 or more on a 9x7 torus, all but the 5x5 square around the player
+        assert_eq!(reached.len(), 9 * 7 - 5 * 5);
+        assert!(chi_squared(&reached) < chi_squared_threshold(reached.len() - 1));
+
+        // the farthest spawn distance always has a cell
+        for (width, height) in [(2, 2), (99, 2), (4, 9)] {
+            let spawn_distance = Geometry::max_spawn_distance(width, height);
+            let geometry = Geometry::new(width, height, 1, spawn_distance).unwrap();
+            let (player, treasure) =
+                Board::spawn_coordinates(&mut Xoshiro256StarStar::seed_from_u64(8), &geometry);
+            let dist = Position::get_shortest_dist(player.get_xy_dists(&treasure), &geometry);
+            assert_eq!(dist, spawn_distance);
+        }
+    }
+
     #[test]
     /// I don't test if it's in board here as it's tested in the coordinate modulator tests
     fn set_player_coordinates_works() {
@@ -0,0 +1,204 @@
+use super::{input, BoardError, Command, FromStr};
+use std::{fs, io};
+
+/// The custom key bindings of the player, loaded from a file at startup
+///
+/// each key is a word, a letter or a number, bound to a command text
+/// the text is parsed like a typed command, so it can be a command name like `search`,
+/// a zmove like `8,2` or a whole chain like `8,2 6,3 s`, which makes it a macro
+/// a bound key wins over the built-in shortcuts, the command texts never use the bindings
+///
+/// # Attributes
+/// * `keys` - each key, in lowercase, and its command text, in the file order
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Bindings {
+    keys: Vec<(String, String)>,
+}
+
+impl Bindings {
+    /// loads the bindings from a file, see `Bindings::parse`
+    ///
+    /// # Arguments
+    /// * `path` - the file to load
+    ///
+    /// # Returns
+    /// * `Bindings` - the loaded bindings
+    /// * `Err` - if the file can't be read or isn't valid
+    pub fn from_file(path: &str) -> io::Result<Bindings> {
+        Bindings::parse(&fs::read_to_string(path)?)
+    }
+
+    /// parses bindings, one `key = command` per line
+    /// empty lines and lines starting with `#` are ignored
+    ///
+    /// the conflicts are refused: a key bound twice, a key that would be read as a zmove
+    /// or a chain, and a command text that isn't a valid command
+    ///
+    /// # Arguments
+    /// * `content` - the content of a bindings file
+    ///
+    /// # Returns
+    /// * `Bindings` - the parsed bindings
+    /// * `Err` - of kind `InvalidData`, telling the line and the problem
+    pub fn parse(content: &str) -> io::Result<Bindings> {
+        let invalid = |line: usize, message: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}, {}", line, message),
+            )
+        };
+        let mut bindings = Bindings::default();
+        let mut lines: Vec<usize> = Vec::new(); // where each key was bound
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, text) = match line.split_once('=') {
+                Some((key, text)) => (key.trim().to_lowercase(), text.trim().to_string()),
+                None => {
+                    return Err(invalid(
+                        index + 1,
+                        "a binding is written 'key = command'".to_string(),
+                    ))
+                }
+            };
+
+            let is_zmove_like = key.contains(|c: char| c.is_whitespace() || c == ',' || c == ';')
+                || matches!(
+                    Command::from_str(&key),
+                    Ok(Command::Zmove(_)) | Ok(Command::Sequence { .. })
+                );
+            if key.is_empty() || is_zmove_like {
+                return Err(invalid(
+                    index + 1,
+                    format!(
+                        "the key '{}' can't be empty or look like a zmove, it would never be read",
+                        key
+                    ),
+                ));
+            }
+            if let Some(position) = bindings.keys.iter().position(|(bound, _)| *bound == key) {
+                return Err(invalid(
+                    index + 1,
+                    format!(
+                        "the key '{}' is already bound line {}",
+                        key, lines[position]
+                    ),
+                ));
+            }
+            if let Err(err) = Command::from_str(&text) {
+                return Err(invalid(
+                    index + 1,
+                    format!(
+                        "the command of '{}' isn't valid, {}",
+                        key,
+                        input::format_error(&err)
+                    ),
+                ));
+            }
+
+            bindings.keys.push((key, text));
+            lines.push(index + 1);
+        }
+        Ok(bindings)
+    }
+
+    /// parses a typed command, the bound keys first, then the built-in commands
+    ///
+    /// # Arguments
+    /// * `s` - the command as the user typed it
+    ///
+    /// # Returns
+    /// * `Command` - the bound command or the built-in one
+    /// * `BoardError` - the errors of `Command::from_str`
+    pub fn parse_command(&self, s: &str) -> Result<Command, BoardError> {
+        let key = s.trim().to_lowercase();
+        match self.keys.iter().find(|(bound, _)| *bound == key) {
+            Some((_, text)) => Command::from_str(text),
+            None => Command::from_str(s),
+        }
+    }
+
+    /// the bound keys and their command text, in the file order
+    pub fn keys(&self) -> &[(String, String)] {
+        &self.keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Zmove;
+    use super::*;
+
+    #[test]
+    fn valid_bindings() {
+        let bindings = Bindings::parse(
+            "# azerty\n\
+             a = quit\n\
+             \n\
+             Avance = move\n\
+             9 = search\n\
+             zz = 8,2 6x3 s\n",
+        )
+        .unwrap();
+
+        assert_eq!(bindings.keys().len(), 4);
+        assert_eq!(bindings.parse_command("A").unwrap(), Command::Quit);
+        assert_eq!(
+            bindings.parse_command(" avance ").unwrap(),
+            Command::AskTeleport
+        );
+        assert_eq!(bindings.parse_command("9").unwrap(), Command::Search);
+        assert_eq!(
+            bindings.parse_command("zz").unwrap(),
+            Command::Sequence {
+                zmoves: vec![Zmove::new(8, 2).unwrap(), Zmove::new(6, 3).unwrap()],
+                search: true
+            }
+        );
+        // the built-in commands still work
+        assert_eq!(bindings.parse_command("q").unwrap(), Command::Quit);
+        assert_eq!(
+            bindings.parse_command("8,1").unwrap(),
+            Command::Zmove(Zmove::new(8, 1).unwrap())
+        );
+    }
+
+    #[test]
+    fn conflicting_bindings() {
+        let err = Bindings::parse("a = quit\nb = move\nA = search\n").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "line 3, the key 'a' is already bound line 1"
+        );
+
+        let err = Bindings::parse("8,2 = search\n").unwrap_err();
+        assert!(err.to_string().starts_with("line 1, the key '8,2'"));
+
+        let err = Bindings::parse("8x2 = search\n").unwrap_err();
+        assert!(err.to_string().starts_with("line 1, the key '8x2'"));
+
+        let err = Bindings::parse("g o = search\n").unwrap_err();
+        assert!(err.to_string().starts_with("line 1, the key 'g o'"));
+
+        let err = Bindings::parse("a = fly\n").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("line 1, the command of 'a' isn't valid, E02"));
+
+        let err = Bindings::parse("a = 8,9\n").unwrap_err();
+        assert!(err.to_string().contains("E01"));
+
+        assert!(Bindings::parse("just a line\n").is_err());
+    }
+
+    #[test]
+    fn empty_bindings_change_nothing() {
+        let bindings = Bindings::parse("# nothing yet\n").unwrap();
+        assert_eq!(bindings, Bindings::default());
+        assert_eq!(bindings.parse_command("s").unwrap(), Command::Search);
+    }
+}
@@ -1,4 +1,4 @@
-use super::{assert_matches, BoardError, FromStr, Zmove};
+use super::{parser, BoardError, Direction, ErrorContext, FromStr, Item, Zmove};
 
 /// A Command used to determine what should be done
 /// I don't know how to comment an enum
@@ -9,6 +9,137 @@ pub enum Command {
     Zmove(Zmove),
     Search,
     Quit,
+    Export,
+    Sequence { zmoves: Vec<Zmove>, search: bool }, // zmoves done in one turn, maybe followed by a search
+    ToggleReach,
+    UseItem(Item),
+    ToggleCandidates,
+    CycleHeatmap,
+    ListSearches,
+    Pan(Option<Direction>), // moves the view half a screen, or back on the player if none
+}
+
+/// tells if a part of a command looks like a zmove step, `8,2` `(0x8,2)` or `8x2`
+fn is_step_like(part: &str) -> bool {
+    part.contains(|c: char| c.is_ascii_digit())
+        && part
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() || ['x', ',', '(', ')', '[', ']', '-'].contains(&c))
+}
+
+/// finds the `x` separating the direction from the speed in a step like `8x2` or `0x8x0x2`
+/// the `x` of a `0x` prefix is not a separator
+///
+/// # Arguments
+/// * `step` - a step like part, it's ascii only
+///
+/// # Returns
+/// * `Option<usize>` - the index of the separator if there is one
+fn step_separator(step: &str) -> Option<usize> {
+    let bytes = step.as_bytes();
+    (0..bytes.len()).find(|&i| {
+        let is_prefix =
+            i >= 1 && bytes[i - 1] == b'0' && (i == 1 || !bytes[i - 2].is_ascii_alphanumeric());
+        bytes[i] == b'x' && !is_prefix
+    })
+}
+
+/// parses a chain of zmoves like `8,2 6,3 s` or `8x2;6x3;s`
+/// the steps are separated by spaces or `;`, a search can only be the last step
+/// every step is validated, one error rejects the whole chain
+///
+/// # Arguments
+/// * `s` - the command as the user typed it
+///
+/// # Returns
+/// * `None` - if `s` isn't a chain, the other commands handle it
+/// * `Some(Ok(Command))` - a `Command::Zmove` for a lone `8x2`, or a `Command::Sequence`
+/// * `Some(Err(BoardError))` - the first error of the chain, located in the whole input
+fn parse_sequence(s: &str) -> Option<Result<Command, BoardError>> {
+    // the spaces around the commas are inside a step, the other ones separate the steps
+    let input = s
+        .split(',')
+        .map(|value| value.trim())
+        .collect::<Vec<&str>>()
+        .join(",");
+    let input = input.trim();
+
+    let mut parts: Vec<(String, usize)> = Vec::new();
+    for (column, c) in input.chars().enumerate() {
+        match (c.is_whitespace() || c == ';', parts.last_mut()) {
+            (true, _) => (),
+            (false, Some((part, start))) if *start + part.chars().count() == column => part.push(c),
+            (false, _) => parts.push((c.to_string(), column)),
+        }
+    }
+
+    let search = matches!(
+        parts.last().map(|(part, _)| part.to_lowercase()).as_deref(),
+        Some("s") | Some("search")
+    );
+    if search {
+        parts.pop();
+    }
+    if parts.is_empty() || !parts.iter().all(|(part, _)| is_step_like(part)) {
+        return None;
+    }
+    let is_lone_comma_step = parts.len() == 1 && !search && parts[0].0.contains(',');
+    let is_lone_number = parts.len() == 1 && !search && step_separator(&parts[0].0).is_none();
+    if is_lone_comma_step || is_lone_number {
+        return None; // a lone `8,2` is the usual zmove shortcut, a lone `0x8` is a number
+    }
+
+    let mut zmoves: Vec<Zmove> = Vec::new();
+    for (part, column) in parts {
+        let pair = match (part.contains(','), step_separator(&part)) {
+            (true, _) => part,
+            (false, Some(separator)) => {
+                format!("{},{}", &part[..separator], &part[separator + 1..])
+            }
+            (false, None) => {
+                return Some(Err(BoardError::InvalidFormat(
+                    ErrorContext::new("Each step is a direction and a speed, like '8,2' or '8x2'")
+                        .with_input(input)
+                        .at_column(column),
+                )))
+            }
+        };
+        match Zmove::from_str(&pair) {
+            Ok(zmove) => zmoves.push(zmove),
+            Err(err) => return Some(Err(parser::relocate(err, input, column))),
+        }
+    }
+
+    Some(Ok(match (zmoves.len(), search) {
+        (1, false) => Command::Zmove(zmoves.remove(0)),
+        _ => Command::Sequence { zmoves, search },
+    }))
+}
+
+/// parses a pan of the view like `pan 8` or `p4`, a lone `pan` or `p5` centers it back on the player
+///
+/// # Arguments
+/// * `s` - the command without its spaces
+///
+/// # Returns
+/// * `None` - if `s` isn't a pan, the other commands handle it
+/// * `Some(Ok(Command::Pan))` - the pan and its direction
+/// * `Some(Err(BoardError::InvalidMove))` - if the digit isn't a num pad direction
+fn parse_pan(s: &str) -> Option<Result<Command, BoardError>> {
+    let lower = s.to_lowercase();
+    let digit = lower
+        .strip_prefix("pan")
+        .or_else(|| lower.strip_prefix('p'))?;
+    match digit {
+        "" | "5" => Some(Ok(Command::Pan(None))),
+        _ => match digit.parse::<u32>() {
+            Ok(direction) => Some(
+                Direction::get_direction_from_num_pad_int(direction)
+                    .map(|direction| Command::Pan(Some(direction))),
+            ),
+            Err(_) => None,
+        },
+    }
 }
 
 impl FromStr for Command {
@@ -24,9 +155,18 @@ impl FromStr for Command {
     /// * `BoardError::InvalidCommand(String)` - when the str is parsed but not what we want
     /// * `BoardError::FailedParse(String)` - when the parsing failed like when we parse for u32 but get a negative number
     /// * `BoardError::TooManyArguments(usize)` - when the number of arguments separeted by ',' is bigger than 2
-    /// * `BoardError::InvalidMove` - if the quick zmove isn't correct
+    /// * `BoardError::InvalidMove` - if the quick zmove or the pan direction isn't correct
+    /// * `BoardError::InvalidFormat` - if a step of a zmove chain isn't a zmove
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let clean_s = s.trim().replace(|x| x == ' ', ""); //we got rid of spaces
+        if let Some(sequence) = parse_sequence(s) {
+            return sequence;
+        }
+
+        let clean_s = s.trim().replace(' ', ""); //we got rid of spaces
+
+        if let Some(pan) = parse_pan(&clean_s) {
+            return pan;
+        }
 
         if !clean_s
             .contains(|c| ['1', '2', '3', '4', '5', '6', '7', '8', '9', '0', ','].contains(&c))
@@ -37,9 +177,17 @@ impl FromStr for Command {
                 "move" | "m" => return Ok(Command::AskTeleport),
                 "search" | "s" => return Ok(Command::Search),
                 "exit" | "quit" | "q" | "e" => return Ok(Command::Quit),
+                "export" | "x" => return Ok(Command::Export),
+                "reach" | "r" => return Ok(Command::ToggleReach),
+                "sonar" | "o" => return Ok(Command::UseItem(Item::Sonar)),
+                "compass" | "c" => return Ok(Command::UseItem(Item::Compass)),
+                "detector" | "d" => return Ok(Command::UseItem(Item::MetalDetector)),
+                "candidates" | "k" => return Ok(Command::ToggleCandidates),
+                "heatmap" | "h" => return Ok(Command::CycleHeatmap),
+                "searches" | "l" => return Ok(Command::ListSearches),
                 _ => {
                     return Err(BoardError::InvalidCommand(
-                        "This word is not recognised".to_string(),
+                        ErrorContext::new("This word is not recognised").with_input(&clean_s),
                     ))
                 }
             }
@@ -48,17 +196,27 @@ impl FromStr for Command {
         if number_value.len() == 1 {
             // number choice
             match number_value[0].parse::<u32>() {
-                Ok(0) => return Ok(Command::AskTeleport),
-                Ok(1) => return Ok(Command::Search),
-                Ok(2) => return Ok(Command::Quit),
-                Ok(_) => {
-                    return Err(BoardError::InvalidCommand(
-                        "This number doesn't correspond to any associated choice".to_string(),
-                    ))
-                }
-                Err(_) => return Err(BoardError::FailedParse(
-                    "You need to enter an u32 corresponding to the command, or the command itself"
-                        .to_string(),
+                Ok(0) => Ok(Command::AskTeleport),
+                Ok(1) => Ok(Command::Search),
+                Ok(2) => Ok(Command::Quit),
+                Ok(3) => Ok(Command::Export),
+                Ok(4) => Ok(Command::ToggleReach),
+                Ok(5) => Ok(Command::UseItem(Item::Sonar)),
+                Ok(6) => Ok(Command::UseItem(Item::Compass)),
+                Ok(7) => Ok(Command::UseItem(Item::MetalDetector)),
+                Ok(8) => Ok(Command::ToggleCandidates),
+                Ok(9) => Ok(Command::CycleHeatmap),
+                Ok(10) => Ok(Command::ListSearches),
+                Ok(_) => Err(BoardError::InvalidCommand(
+                    ErrorContext::new("This number doesn't correspond to any associated choice")
+                        .with_input(&clean_s)
+                        .expecting(0, 10),
+                )),
+                Err(_) => Err(BoardError::FailedParse(
+                    ErrorContext::new(
+                        "You need to enter an u32 corresponding to the command, or the command itself",
+                    )
+                    .with_input(&clean_s),
                 )),
             }
         } else if number_value.len() == 2 {
@@ -70,7 +228,7 @@ impl FromStr for Command {
             }
         } else {
             // incorrect number of parameters
-            return Err(BoardError::TooManyArguments(number_value.len()));
+            Err(BoardError::TooManyArguments(number_value.len()))
         }
     }
 }
@@ -78,6 +236,7 @@ impl FromStr for Command {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use matches::assert_matches;
     #[test]
     fn valid_command_from_str() {
         assert_eq!(Command::from_str("move").unwrap(), Command::AskTeleport);
@@ -100,6 +259,52 @@ mod tests {
         assert_eq!(Command::from_str("e").unwrap(), Command::Quit);
         assert_eq!(Command::from_str("2").unwrap(), Command::Quit);
 
+        assert_eq!(Command::from_str("export").unwrap(), Command::Export);
+        assert_eq!(Command::from_str("x").unwrap(), Command::Export);
+        assert_eq!(Command::from_str("3").unwrap(), Command::Export);
+
+        assert_eq!(Command::from_str("reach").unwrap(), Command::ToggleReach);
+        assert_eq!(Command::from_str("r").unwrap(), Command::ToggleReach);
+        assert_eq!(Command::from_str("4").unwrap(), Command::ToggleReach);
+
+        assert_eq!(
+            Command::from_str("sonar").unwrap(),
+            Command::UseItem(Item::Sonar)
+        );
+        assert_eq!(
+            Command::from_str("c").unwrap(),
+            Command::UseItem(Item::Compass)
+        );
+        assert_eq!(
+            Command::from_str("7").unwrap(),
+            Command::UseItem(Item::MetalDetector)
+        );
+
+        assert_eq!(
+            Command::from_str("candidates").unwrap(),
+            Command::ToggleCandidates
+        );
+        assert_eq!(Command::from_str("k").unwrap(), Command::ToggleCandidates);
+        assert_eq!(Command::from_str("8").unwrap(), Command::ToggleCandidates);
+        assert_eq!(Command::from_str("h").unwrap(), Command::CycleHeatmap);
+        assert_eq!(Command::from_str("9").unwrap(), Command::CycleHeatmap);
+        assert_eq!(
+            Command::from_str("searches").unwrap(),
+            Command::ListSearches
+        );
+        assert_eq!(Command::from_str("10").unwrap(), Command::ListSearches);
+
+        assert_eq!(
+            Command::from_str("pan 8").unwrap(),
+            Command::Pan(Some(Direction::Up))
+        );
+        assert_eq!(
+            Command::from_str("P1").unwrap(),
+            Command::Pan(Some(Direction::DownLeft))
+        );
+        assert_eq!(Command::from_str("pan").unwrap(), Command::Pan(None));
+        assert_eq!(Command::from_str("p5").unwrap(), Command::Pan(None));
+
         assert_eq!(Command::from_str("zmove").unwrap(), Command::AskZmove);
         assert_eq!(Command::from_str("z").unwrap(), Command::AskZmove);
 
@@ -158,6 +363,15 @@ mod tests {
             BoardError::InvalidFormat(_)
         );
 
+        assert_matches!(
+            Command::from_str("pan 0").unwrap_err(),
+            BoardError::InvalidMove(_)
+        );
+        assert_matches!(
+            Command::from_str("pan up").unwrap_err(),
+            BoardError::InvalidCommand(_)
+        );
+
         //zmoves errors, more are tested in zmove.rs
         assert_matches!(
             Zmove::from_str("0,1").unwrap_err(),
@@ -181,4 +395,61 @@ mod tests {
             BoardError::TooManyArguments(_)
         );
     }
+
+    #[test]
+    fn valid_sequence_from_str() {
+        assert_eq!(
+            Command::from_str("8,2 6,3 s").unwrap(),
+            Command::Sequence {
+                zmoves: vec![Zmove::new(8, 2).unwrap(), Zmove::new(6, 3).unwrap()],
+                search: true
+            }
+        );
+        assert_eq!(
+            Command::from_str("8x2 6x3 ;s").unwrap(),
+            Command::from_str("8,2 6,3 s").unwrap()
+        );
+        assert_eq!(
+            Command::from_str("(8 , 2);0x6x0x3").unwrap(),
+            Command::Sequence {
+                zmoves: vec![Zmove::new(8, 2).unwrap(), Zmove::new(6, 3).unwrap()],
+                search: false
+            }
+        );
+        // a single step is a plain zmove
+        assert_eq!(
+            Command::from_str("8x2").unwrap(),
+            Command::Zmove(Zmove::new(8, 2).unwrap())
+        );
+        assert_eq!(
+            Command::from_str("1,1 search").unwrap(),
+            Command::Sequence {
+                zmoves: vec![Zmove::new(1, 1).unwrap()],
+                search: true
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_sequence_from_str() {
+        // the whole chain is refused, pointing at the bad step
+        let err = Command::from_str("8,2 6,9 s").unwrap_err();
+        assert_matches!(err, BoardError::InvalidMove(_));
+        assert_eq!(err.context().unwrap().input.as_deref(), Some("8,2 6,9 s"));
+        assert_eq!(err.context().unwrap().column, Some(6));
+
+        let err = Command::from_str("8x2;0x5x1").unwrap_err();
+        assert_matches!(err, BoardError::InvalidMove(_));
+        assert_eq!(err.context().unwrap().column, Some(4));
+
+        let err = Command::from_str("8x2 3").unwrap_err();
+        assert_matches!(err, BoardError::InvalidFormat(_));
+        assert_eq!(err.context().unwrap().column, Some(4));
+
+        // a search is only accepted at the end
+        assert_matches!(
+            Command::from_str("s 8,2").unwrap_err(),
+            BoardError::InvalidFormat(_)
+        );
+    }
 }
@@ -0,0 +1,487 @@
+use super::{
+    input, Board, Color, FromStr, GameSettings, Geometry, HintNoise, MoveSet, Palette, Tile,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// The configuration file of the game, in toml, kept in the user config directory
+///
+/// ```toml
+/// profile = "easy" # the profile the settings menu starts with
+///
+/// [profiles.easy]
+/// seed = 2
+/// color = "green"
+/// palette = "deuteranopia"
+/// reach = 6
+/// direction_hints = true
+///
+/// [profiles.team-race]
+/// seed = 42
+/// width = 30
+/// height = 20
+/// spawn_distance = 8
+/// move_set = "knight"
+/// distance_hints = false
+/// fog_radius = 3
+/// hint_noise = { spread = 1, noise_percent = 30, lie_percent = 10 }
+/// ```
+///
+/// # Attributes
+/// * `profile` - the name of the profile used at startup, the default settings on a random seed if there is none
+/// * `profiles` - each named profile, sorted by name
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// A named set of settings, every missing value is the default one
+///
+/// # Attributes
+/// * `seed` - the seed of the board, a random one if it's missing
+/// * `color` - the player color, written like in the settings menu, `red` `12` `23,144,64`
+///   it's refused if it can't be seen on the water, the palette's own color is used if it's missing
+/// * `palette` - the colors of the water and of the searched cells, `standard` if it's missing
+/// * `tile` - the player tile, a single character or emoji
+/// * `move_set` - a named move set, or `custom` with the `offsets`
+/// * `offsets` - the `[dx, dy]` of a custom move set
+/// * `width` `height` `reach` - the board size and the reach of a move
+/// * `spawn_distance` - the smallest distance between the player and the treasure at the start
+/// * `distance_hints` - if a failed search tells the distance to the treasure
+/// * `direction_hints` - if a failed search tells the direction of the treasure
+/// * `zmoves` - if the zmoves and the chains can be used
+/// * `fog_radius` - how far the player sees, the whole board is drawn if it's missing
+/// * `hint_noise` - how the distance hints are blurred, they are exact if it's missing
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub palette: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_set: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offsets: Option<Vec<(i64, i64)>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reach: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spawn_distance: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_hints: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction_hints: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zmoves: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fog_radius: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint_noise: Option<HintNoise>,
+}
+
+impl Config {
+    /// where the configuration file is, `$XDG_CONFIG_HOME` or `~/.config` on linux
+    ///
+    /// # Returns
+    /// * `Some(PathBuf)` - the path of the file, it may not exist yet
+    /// * `None` - if the system has no config directory
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(Board::CONFIG_DIR).join(Board::CONFIG_FILE))
+    }
+
+    /// loads the configuration from a file, see `Config::parse`
+    ///
+    /// # Arguments
+    /// * `path` - the file to load
+    ///
+    /// # Returns
+    /// * `Config` - the loaded configuration
+    /// * `Err` - if the file can't be read or isn't valid
+    pub fn from_file(path: &Path) -> io::Result<Config> {
+        Config::parse(&fs::read_to_string(path)?)
+    }
+
+    /// parses a configuration, a wrong profile doesn't refuse the others, see `Config::warnings`
+    ///
+    /// # Arguments
+    /// * `content` - the toml content
+    ///
+    /// # Returns
+    /// * `Config` - the parsed configuration
+    /// * `Err` - of kind `InvalidData`, telling the toml error or the startup profile that doesn't exist
+    pub fn parse(content: &str) -> io::Result<Config> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let config: Config = toml::from_str(content).map_err(|err| invalid(err.to_string()))?;
+
+        match &config.profile {
+            Some(name) if !config.profiles.contains_key(name) => Err(invalid(format!(
+                "the profile '{}' is used but never written",
+                name
+            ))),
+            _ => Ok(config),
+        }
+    }
+
+    /// checks every profile, a wrong one is kept in the file but it can't be loaded
+    ///
+    /// # Returns
+    /// * `Vec<String>` - what is wrong in each wrong profile, like `profile 'a', the color 'sea' isn't valid`
+    pub fn warnings(&self) -> Vec<String> {
+        self.profiles
+            .iter()
+            .filter_map(|(name, profile)| {
+                profile
+                    .to_settings()
+                    .err()
+                    .map(|message| format!("profile '{}', {}", name, message))
+            })
+            .collect()
+    }
+
+    /// writes the configuration, the directory is created if needed
+    ///
+    /// # Arguments
+    /// * `path` - the file to write
+    ///
+    /// # Returns
+    /// * `Ok` - if the file was written
+    /// * `Err` - if the directory or the file can't be written
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, content)
+    }
+
+    /// the settings of a profile
+    ///
+    /// # Arguments
+    /// * `name` - the name of the profile
+    ///
+    /// # Returns
+    /// * `Some(GameSettings)` - the settings of the profile
+    /// * `None` - if there is no such profile, or if it's wrong, see `Config::warnings`
+    pub fn profile_settings(&self, name: &str) -> Option<GameSettings> {
+        self.profiles
+            .get(name)
+            .and_then(|profile| profile.to_settings().ok())
+    }
+
+    /// the settings the settings menu starts with
+    ///
+    /// # Returns
+    /// * `GameSettings` - the settings of the startup profile, or the default ones
+    pub fn starting_settings(&self) -> GameSettings {
+        self.profile
+            .as_deref()
+            .and_then(|name| self.profile_settings(name))
+            .unwrap_or_else(GameSettings::get_new_settings)
+    }
+}
+
+impl Profile {
+    /// the settings of this profile, the missing values are the default ones
+    /// a profile without a seed gets a random one each time
+    ///
+    /// # Returns
+    /// * `GameSettings` - the settings
+    /// * `Err(String)` - telling which value is wrong
+    pub fn to_settings(&self) -> Result<GameSettings, String> {
+        let mut settings = GameSettings::get_new_settings();
+        if let Some(seed) = self.seed {
+            settings.seed = seed;
+        }
+        if let Some(palette) = &self.palette {
+            settings.palette = Palette::from_str(palette)
+                .map_err(|_| format!("the palette '{}' isn't valid", palette))?;
+            settings.player_color = settings.palette.player_color();
+        }
+        if let Some(color) = &self.color {
+            settings.player_color =
+                Color::from_str(color).map_err(|_| format!("the color '{}' isn't valid", color))?;
+            settings
+                .palette
+                .check_player_color(&settings.player_color)
+                .map_err(|err| format!("the color '{}' is refused, {}", color, err))?;
+        }
+        if let Some(tile) = &self.tile {
+            settings.player_tile = Tile::from_str(tile)
+                .map_err(|_| format!("the tile '{}' isn't a single character", tile))?;
+        }
+        settings.move_set = match (self.move_set.as_deref(), &self.offsets) {
+            (Some("custom"), Some(offsets)) if !offsets.is_empty() => {
+                MoveSet::check_offsets(offsets)?;
+                MoveSet::Custom(offsets.clone())
+            }
+            (Some("custom"), _) => return Err("a custom move set needs offsets".to_string()),
+            (_, Some(_)) => return Err("the offsets are only for a custom move set".to_string()),
+            (Some(name), None) => {
+                MoveSet::from_str(name).map_err(|err| input::format_error(&err))?
+            }
+            (None, None) => settings.move_set,
+        };
+        let default = settings.geometry;
+        settings.geometry = Geometry::new(
+            self.width.unwrap_or(default.width),
+            self.height.unwrap_or(default.height),
+            self.reach.unwrap_or(default.reach),
+            self.spawn_distance.unwrap_or(default.spawn_distance),
+        )?;
+        if let Some(distance_hints) = self.distance_hints {
+            settings.distance_hints = distance_hints;
+        }
+        if let Some(direction_hints) = self.direction_hints {
+            settings.direction_hints = direction_hints;
+        }
+        if let Some(zmoves) = self.zmoves {
+            settings.zmoves_allowed = zmoves;
+        }
+        if self.fog_radius.is_some() {
+            settings.fog_radius = self.fog_radius;
+        }
+        if let Some(noise) = self.hint_noise {
+            // checked again, the toml doesn't go through `HintNoise::new`
+            settings.hint_noise = Some(HintNoise::new(
+                noise.spread,
+                noise.noise_percent,
+                noise.lie_percent,
+            )?);
+        }
+        Ok(settings)
+    }
+
+    /// the profile of some settings, every value is written
+    ///
+    /// # Arguments
+    /// * `settings` - the settings to save
+    ///
+    /// # Returns
+    /// * `Profile` - the profile, `to_settings` gives the same settings back
+    pub fn from_settings(settings: &GameSettings) -> Profile {
+        let (move_set, offsets) = match &settings.move_set {
+            MoveSet::Custom(offsets) => ("custom".to_string(), Some(offsets.clone())),
+            named => (named.to_string(), None),
+        };
+        Profile {
+            seed: Some(settings.seed),
+            color: Some(color_setting(&settings.player_color)),
+            palette: Some(settings.palette.to_string()),
+            tile: Some(settings.player_tile.to_string()),
+            move_set: Some(move_set),
+            offsets,
+            width: Some(settings.geometry.width),
+            height: Some(settings.geometry.height),
+            reach: Some(settings.geometry.reach),
+            spawn_distance: Some(settings.geometry.spawn_distance),
+            distance_hints: Some(settings.distance_hints),
+            direction_hints: Some(settings.direction_hints),
+            zmoves: Some(settings.zmoves_allowed),
+            fog_radius: settings.fog_radius,
+            hint_noise: settings.hint_noise,
+        }
+    }
+}
+
+/// writes a color the way `Color::from_str` reads it
+///
+/// # Arguments
+/// * `color` - the color to write
+///
+/// # Returns
+/// * `String` - a name, an ansi number or `r,g,b`
+fn color_setting(color: &Color) -> String {
+    match color {
+        Color::Ansi256(value) => value.to_string(),
+        Color::Rgb(r, g, b) => format!("{},{},{}", r, g, b),
+        named => format!("{:?}", named).to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_fill_the_defaults() {
+        let config = Config::parse(
+            "profile = \"hard\"\n\
+             \n\
+             [profiles.easy]\n\
+             color = \"green\"\n\
+             palette = \"deuteranopia\"\n\
+             reach = 6\n\
+             direction_hints = true\n\
+             \n\
+             [profiles.hard]\n\
+             seed = 7\n\
+             color = \"20,130,200\"\n\
+             move_set = \"knight\"\n\
+             distance_hints = false\n\
+             fog_radius = 3\n\
+             hint_noise = { spread = 1, noise_percent = 30, lie_percent = 10 }\n\
+             \n\
+             [profiles.team-race]\n\
+             width = 30\n\
+             height = 20\n\
+             move_set = \"custom\"\n\
+             offsets = [[3, 1], [-3, -1]]\n\
+             zmoves = false\n",
+        )
+        .unwrap();
+
+        let hard = config.starting_settings();
+        assert_eq!(hard.seed, 7);
+        assert_eq!(hard.player_color, Color::Rgb(20, 130, 200));
+        assert_eq!(hard.palette, Palette::Standard);
+        assert_eq!(hard.move_set, MoveSet::Knight);
+        assert!(!hard.distance_hints);
+        assert_eq!(hard.fog_radius, Some(3));
+        assert_eq!(hard.hint_noise, Some(HintNoise::new(1, 30, 10).unwrap()));
+        assert_eq!(hard.player_tile, Tile::from(Board::DEFAULT_PLAYER_TILE));
+
+        let easy = config.profile_settings("easy").unwrap();
+        assert_eq!(easy.player_color, Color::Green);
+        assert_eq!(easy.palette, Palette::Deuteranopia);
+        assert_eq!(easy.geometry.reach, 6);
+        assert!(easy.direction_hints);
+        assert_ne!(easy.seed, config.profile_settings("easy").unwrap().seed);
+
+        let race = config.profile_settings("team-race").unwrap();
+        assert_eq!(race.geometry, Geometry::new(30, 20, 4, 2).unwrap());
+        assert_eq!(race.move_set, MoveSet::Custom(vec![(3, 1), (-3, -1)]));
+        assert!(!race.zmoves_allowed);
+
+        // the palette comes with its own player color
+        let tritanopia = Config::parse("[profiles.a]\npalette = \"tritanopia\"\n").unwrap();
+        let settings = tritanopia.profile_settings("a").unwrap();
+        assert_eq!(settings.player_color, Palette::Tritanopia.player_color());
+
+        assert_eq!(config.profile_settings("nightmare"), None);
+        assert_eq!(
+            GameSettings {
+                seed: Board::DEFAULT_SEED,
+                ..Config::default().starting_settings()
+            },
+            GameSettings::get_default_settings()
+        );
+    }
+
+    #[test]
+    fn wrong_profiles_are_refused() {
+        let message = |content: &str| Config::parse(content).unwrap().warnings().join("\n");
+
+        assert_eq!(
+            message("[profiles.a]\ncolor = \"sea\"\n"),
+            "profile 'a', the color 'sea' isn't valid"
+        );
+        assert_eq!(
+            message("[profiles.a]\ncolor = \"20,30,200\"\n"),
+            "profile 'a', the color '20,30,200' is refused, the contrast with the water is 1.09:1, you couldn't be seen on it"
+        );
+        assert_eq!(
+            message("[profiles.a]\npalette = \"sepia\"\n"),
+            "profile 'a', the palette 'sepia' isn't valid"
+        );
+        assert_eq!(
+            message("[profiles.a]\ntile = \"ab\"\n"),
+            "profile 'a', the tile 'ab' isn't a single character"
+        );
+        assert_eq!(
+            message("[profiles.a]\nwidth = 3\n"),
+            "profile 'a', the reach 4 has to be between 1 and 2"
+        );
+        assert_eq!(
+            message("[profiles.a]\nmove_set = \"custom\"\n"),
+            "profile 'a', a custom move set needs offsets"
+        );
+        assert_eq!(
+            message(
+                "[profiles.a]\nmove_set = \"custom\"\noffsets = [[1, 2], [9223372036854775807, 0]]\n"
+            ),
+            "profile 'a', the offset [9223372036854775807, 0] is longer than the biggest board side 10000"
+        );
+        assert!(message("[profiles.a]\nmove_set = \"queen\"\n").starts_with("profile 'a', E02"));
+        assert_eq!(
+            Config::parse("profile = \"b\"\n[profiles.a]\n")
+                .unwrap_err()
+                .to_string(),
+            "the profile 'b' is used but never written"
+        );
+        assert_eq!(
+            message(
+                "[profiles.a]\nhint_noise = { spread = 1, noise_percent = 80, lie_percent = 30 }\n"
+            ),
+            "profile 'a', the noise 80% and the lies 30% add up over 100%"
+        );
+        // a wrong profile only takes itself out, even the startup one
+        let config = Config::parse(
+            "profile = \"a\"\n[profiles.a]\ncolor = \"sea\"\n[profiles.b]\nseed = 3\n",
+        )
+        .unwrap();
+        assert_eq!(config.warnings().len(), 1);
+        assert_eq!(config.profile_settings("a"), None);
+        assert_eq!(config.profile_settings("b").unwrap().seed, 3);
+        assert_eq!(
+            GameSettings {
+                seed: Board::DEFAULT_SEED,
+                ..config.starting_settings()
+            },
+            GameSettings::get_default_settings()
+        );
+
+        // a typo is refused instead of silently ignored
+        assert!(Config::parse("[profiles.a]\nsede = 3\n").is_err());
+        assert!(Config::parse("profile = 3\n").is_err());
+    }
+
+    #[test]
+    fn saved_profiles_load_back() {
+        let mut settings = GameSettings::get_default_settings();
+        settings.seed = 99;
+        settings.player_color = Color::Ansi256(208);
+        settings.player_tile = Tile::from_str("👍🏽").unwrap();
+        settings.move_set = MoveSet::Custom(vec![(2, -1)]);
+        settings.geometry = Geometry::new(12, 40, 3, 20).unwrap();
+        settings.zmoves_allowed = false;
+        settings.fog_radius = Some(0);
+        settings.hint_noise = Some(HintNoise::new(0, 0, 100).unwrap());
+        settings.palette = Palette::Protanopia;
+
+        let mut config = Config::default();
+        config
+            .profiles
+            .insert("mine".to_string(), Profile::from_settings(&settings));
+        config.profile = Some("mine".to_string());
+
+        let path = std::env::temp_dir()
+            .join("treasure_config_test")
+            .join("config.toml");
+        config.save(&path).unwrap();
+        let loaded = Config::from_file(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(loaded, config);
+        assert_eq!(loaded.starting_settings(), settings);
+        assert_eq!(
+            Profile::from_settings(&GameSettings::get_default_settings())
+                .to_settings()
+                .unwrap(),
+            GameSettings::get_default_settings()
+        );
+    }
+}
@@ -1,24 +1,101 @@
-use super::{Board, Color};
+use super::{Board, Color, MoveSet};
 
 /// the `Board`'s associated constants
 /// they are pub(super) to stop main or any not Board thing to access it
 impl Board {
     pub(super) const DEFAULT_BOARD_WIDTH: u32 = 15;
     pub(super) const DEFAULT_BOARD_HEIGHT: u32 = 15;
+    // the sides a profile can ask for, the tracker packs a cell in a bit so the biggest board stays small
+    pub(super) const MIN_BOARD_SIDE: u32 = 2;
+    pub(super) const MAX_BOARD_SIDE: u32 = 10_000;
+    // the cells drawn around the player when the board is bigger, if the terminal size is unknown
+    pub(super) const VIEWPORT_WIDTH: u32 = 40;
+    pub(super) const VIEWPORT_HEIGHT: u32 = 30;
+    // a terminal too small still gets a view this big
+    pub(super) const VIEWPORT_MIN_SIDE: u32 = 5;
+    // the cells get narrower to fit the whole board in the terminal, from the widest
+    pub(super) const CELL_WIDTHS: [usize; 3] = [3, 2, 1];
+    // the lines of the board frame, the top and bottom borders and the coordinates
+    pub(super) const BOARD_FRAME_LINES: u32 = 3;
+    // the map of the whole board drawn under a bigger one, a char per block of cells
+    // the blocks go from unexplored to fully known
+    pub(super) const MINIMAP_WIDTH: u32 = 40;
+    pub(super) const MINIMAP_HEIGHT: u32 = 10;
+    pub(super) const MINIMAP_TILES: [char; 4] = ['.', '-', '+', '#'];
 
     pub(super) const BOARD_COLOR: Color = Color::White;
 
     pub(super) const WATER_TILE: char = '~';
     pub(super) const DEFAULT_PLAYER_TILE: char = '@';
     pub(super) const TREASURE_TILE: char = 'X';
+    pub(super) const TREASURE_COLOR: Color = Color::Yellow;
+    pub(super) const WATER_COLOR: Color = Color::Blue;
+    // the preview of a zmove chain, drawn in the player color
+    pub(super) const PATH_TILE: char = '*';
+    pub(super) const PATH_END_TILE: char = '+';
+    // the cells in reach keep their color when the preview is on
+    pub(super) const REACH_TILE: char = ':';
+    // the cells hidden by the fog, out of the player sight
+    pub(super) const FOG_TILE: char = '?';
+    pub(super) const FOG_COLOR: Color = Color::White;
+    // the cells that can hold the treasure show their odds in this color
+    pub(super) const CANDIDATE_COLOR: Color = Color::Green;
+    // the heatmap of the searched cells, from the warmest to the coldest
+    pub(super) const HEATMAP_COLORS: [Color; 5] = [
+        Color::Red,
+        Color::Yellow,
+        Color::Green,
+        Color::Cyan,
+        Color::Blue,
+    ];
 
     pub(super) const DEFAULT_SEED: u64 = 2;
     pub(super) const DEFAULT_PLAYER_COLOR: Color = Color::Red;
+    // the contrast ratios of the player color with the water and the board, 1 is the same brightness
+    // below the first one the player color is refused, below the second one there is a warning
+    pub(super) const CONTRAST_REFUSED: f64 = 1.25;
+    pub(super) const CONTRAST_WARNED: f64 = 1.5;
 
     pub(super) const MOVE_MAX_DISTANCE: u32 = 4;
+    // the treasure is never on the player or right next to them at the start
+    pub(super) const DEFAULT_SPAWN_DISTANCE: u32 = 2;
 
-    // special thanks to : https://regexr.com/
-    // warning this regex accepts negative numbers (or nonsensical numbers like 3-4-5)
-    pub(super) const PARENTHESIS_REGEX: &'static str =
-        r"^([(]{1}[0-9,a-fxA-F\-]+[)]{1}$|[\[]{1}[0-9,a-fxA-F\-]+[\]]{1}$|[0-9,a-fxA-F\-]+$)";
+    // the items of a game, how many times each one can be used
+    pub(super) const SONAR_USES: u32 = 2;
+    pub(super) const COMPASS_USES: u32 = 2;
+    pub(super) const METAL_DETECTOR_USES: u32 = 1;
+    pub(super) const SONAR_RADIUS: u32 = 3;
+    // the metal detector searches the 3x3 area around the player
+    pub(super) const METAL_DETECTOR_RADIUS: u32 = 1;
+    pub(super) const DEFAULT_MOVE_SET: MoveSet = MoveSet::Classic;
+
+    // the custom key bindings, read from the working directory at startup
+    pub(super) const BINDINGS_PATH: &'static str = "treasure_bindings.txt";
+
+    // the shareable game codes, Crockford's base 32 has no I L O U so a code can be read aloud
+    // version 2 added the spawn distance
+    pub(super) const GAME_CODE_VERSION: u8 = 2;
+    pub(super) const GAME_CODE_ALPHABET: &'static [u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+    pub(super) const GAME_CODE_GROUP: usize = 4;
+
+    // the configuration file and its profiles, in the user config directory
+    pub(super) const CONFIG_DIR: &'static str = "treasure_hunt";
+    pub(super) const CONFIG_FILE: &'static str = "config.toml";
+
+    // the daily challenges, the salt keeps the daily seeds away from the ones typed by hand
+    pub(super) const DAILY_HISTORY_FILE: &'static str = "daily_history.toml";
+    pub(super) const DAILY_SALT: u64 = 0x7472_6561_7375_7265;
+    pub(super) const DAILY_HISTORY_SHOWN: usize = 7;
+
+    // the exported images use one square cell per tile, the axes take one cell each
+    pub(super) const EXPORT_CELL_SIZE: u32 = 24;
+    // a bigger board would give a huge image, so it's not exported
+    pub(super) const EXPORT_MAX_SIDE: u32 = 99;
+    pub(super) const EXPORT_SVG_PATH: &'static str = "treasure_board.svg";
+    pub(super) const EXPORT_PNG_PATH: &'static str = "treasure_board.png";
+    pub(super) const EXPORT_GIF_PATH: &'static str = "treasure_timeline.gif";
+
+    // gif delays are in hundredths of a second, the last frame stays longer
+    pub(super) const EXPORT_FRAME_DELAY: u16 = 60;
+    pub(super) const EXPORT_LAST_FRAME_DELAY: u16 = 300;
 }
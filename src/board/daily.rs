@@ -0,0 +1,220 @@
+use super::prng::split_mix64;
+use super::{Board, GameSettings};
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// The result of one daily challenge
+///
+/// # Attributes
+/// * `date` - the day of the challenge
+/// * `won` - if the treasure was found, a quit game is lost
+/// * `turns` - the moves, searches and item uses, see `Board::turn`, a zmove chain is a single one
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub date: NaiveDate,
+    pub won: bool,
+    pub turns: u32,
+}
+
+/// Every daily challenge played on this computer, kept in the user data directory
+///
+/// # Attributes
+/// * `days` - one result per day, the first game of the day is the one kept
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct DailyHistory {
+    #[serde(default)]
+    pub days: Vec<DailyResult>,
+}
+
+/// the settings of the challenge of a day, the same for every player
+/// only the seed changes from a day to the next, the rules are the default ones
+///
+/// # Arguments
+/// * `date` - the day of the challenge
+///
+/// # Returns
+/// * `GameSettings` - the settings of that day
+pub fn daily_settings(date: NaiveDate) -> GameSettings {
+    // mixed, so two following days give unrelated seeds
+    let mut state = date.num_days_from_ce() as u64 ^ Board::DAILY_SALT;
+    GameSettings {
+        seed: split_mix64(&mut state),
+        ..GameSettings::get_default_settings()
+    }
+}
+
+impl DailyHistory {
+    /// where the history is, `$XDG_DATA_HOME` or `~/.local/share` on linux
+    ///
+    /// # Returns
+    /// * `Some(PathBuf)` - the path of the file, it may not exist yet
+    /// * `None` - if the system has no data directory
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join(Board::CONFIG_DIR).join(Board::DAILY_HISTORY_FILE))
+    }
+
+    /// loads the history, a missing file is an empty history
+    ///
+    /// # Arguments
+    /// * `path` - the file to load
+    ///
+    /// # Returns
+    /// * `DailyHistory` - the loaded history
+    /// * `Err` - if the file can't be read or isn't valid toml
+    pub fn from_file(path: &Path) -> io::Result<DailyHistory> {
+        if !path.exists() {
+            return Ok(DailyHistory::default());
+        }
+        toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+    }
+
+    /// writes the history, the directory is created if needed
+    ///
+    /// # Arguments
+    /// * `path` - the file to write
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, content)
+    }
+
+    /// the result of a day, if it was played
+    pub fn result_of(&self, date: NaiveDate) -> Option<&DailyResult> {
+        self.days.iter().find(|result| result.date == date)
+    }
+
+    /// records the result of a day, only the first game of a day counts
+    ///
+    /// # Arguments
+    /// * `result` - the result to record
+    ///
+    /// # Returns
+    /// * `bool` - false if the day already had a result, nothing is changed then
+    pub fn record(&mut self, result: DailyResult) -> bool {
+        if self.result_of(result.date).is_some() {
+            return false;
+        }
+        self.days.push(result);
+        self.days.sort_by_key(|result| result.date);
+        true
+    }
+
+    /// the number of days in a row the challenge was won, up to a day
+    /// a day not played yet doesn't break the streak, it can still be won
+    ///
+    /// # Arguments
+    /// * `today` - the last day of the streak
+    ///
+    /// # Returns
+    /// * `u32` - the number of won days in a row
+    pub fn streak(&self, today: NaiveDate) -> u32 {
+        let mut day = match self.result_of(today) {
+            Some(_) => today,
+            None => today - Duration::days(1),
+        };
+        let mut streak = 0;
+        while self.result_of(day).is_some_and(|result| result.won) {
+            streak += 1;
+            day -= Duration::days(1);
+        }
+        streak
+    }
+
+    /// the longest streak ever, see `streak`
+    pub fn best_streak(&self) -> u32 {
+        self.days
+            .iter()
+            .map(|result| self.streak(result.date))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn result(date: &str, won: bool, turns: u32) -> DailyResult {
+        DailyResult {
+            date: day(date),
+            won,
+            turns,
+        }
+    }
+
+    #[test]
+    fn one_board_per_day() {
+        let today = daily_settings(day("2026-10-19"));
+        assert_eq!(today, daily_settings(day("2026-10-19")));
+        assert_ne!(today.seed, daily_settings(day("2026-10-20")).seed);
+        assert_eq!(
+            GameSettings {
+                seed: Board::DEFAULT_SEED,
+                ..today
+            },
+            GameSettings::get_default_settings()
+        );
+    }
+
+    #[test]
+    fn only_the_first_game_counts() {
+        let mut history = DailyHistory::default();
+        assert!(history.record(result("2026-10-19", false, 30)));
+        assert!(!history.record(result("2026-10-19", true, 4)));
+        assert!(history.record(result("2026-10-17", true, 9)));
+
+        assert_eq!(history.result_of(day("2026-10-19")).unwrap().turns, 30);
+        // sorted by date
+        assert_eq!(history.days[0].date, day("2026-10-17"));
+    }
+
+    #[test]
+    fn streaks_count_won_days_in_a_row() {
+        let mut history = DailyHistory::default();
+        for (date, won) in [
+            ("2026-10-10", true),
+            ("2026-10-11", true),
+            ("2026-10-12", true),
+            ("2026-10-13", false),
+            ("2026-10-15", true),
+            ("2026-10-16", true),
+        ] {
+            history.record(result(date, won, 10));
+        }
+
+        assert_eq!(history.streak(day("2026-10-16")), 2);
+        // today isn't played yet, the streak goes on
+        assert_eq!(history.streak(day("2026-10-17")), 2);
+        assert_eq!(history.streak(day("2026-10-18")), 0);
+        assert_eq!(history.streak(day("2026-10-13")), 0);
+        assert_eq!(history.best_streak(), 3);
+        assert_eq!(DailyHistory::default().best_streak(), 0);
+    }
+
+    #[test]
+    fn history_is_saved() {
+        let mut history = DailyHistory::default();
+        history.record(result("2026-10-19", true, 12));
+
+        let path = std::env::temp_dir()
+            .join("treasure_daily_test")
+            .join("daily.toml");
+        assert_eq!(
+            DailyHistory::from_file(&path).unwrap(),
+            DailyHistory::default()
+        );
+        history.save(&path).unwrap();
+        let loaded = DailyHistory::from_file(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert_eq!(loaded, history);
+    }
+}
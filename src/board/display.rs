@@ -1,10 +1,25 @@
-use super::{Board, GameSettings, Position};
+use super::{
+    Bindings, Board, Config, DailyHistory, DailyResult, Direction, GameSettings, Geometry, Heatmap,
+    HintNoise, Inventory, Item, ItemResult, MoveSet, Position, SearchRecord, Tile, Zmove,
+};
+use chrono::NaiveDate;
 use termcolor::{BufferWriter, Color, ColorChoice, ColorSpec, WriteColor};
+use terminal_size::{Height, Width};
 
-use std::io::{self, Write};
+use std::io;
+
+/// gives the size of the terminal the game is played in, the board is drawn to fit it
+///
+/// # Returns
+/// * `Some((u32, u32))` - the columns and the lines of the terminal
+/// * `None` - if stdout isn't a terminal, like when it's piped
+pub fn terminal_dimensions() -> Option<(u32, u32)> {
+    terminal_size::terminal_size()
+        .map(|(Width(columns), Height(lines))| (columns as u32, lines as u32))
+}
 
 /// simple method to print the initial message
-pub fn print_init() -> () {
+pub fn print_init() {
     println!(
         "
     #################################\n
@@ -20,28 +35,320 @@ pub fn print_init() -> () {
 ///
 /// # Arguments
 /// * `game_settings` - a compact way to handle all our settings
-pub fn print_game_settings(game_settings: &GameSettings) -> () {
+/// * `config` - the configuration file, its profiles are listed
+pub fn print_game_settings(game_settings: &GameSettings, config: &Config) {
+    let on_off = |toggle: bool| match toggle {
+        true => "on",
+        false => "off",
+    };
     println!("\n\tYour settings are:");
     println!("\t0: Seed\t\t {}", game_settings.seed);
     println!("\t1: your color\t {:?}", game_settings.player_color);
     println!("\t2: your tile\t {}", game_settings.player_tile);
+    println!("\t3: move set\t {}", game_settings.move_set);
+    println!(
+        "\t4: board size\t {}x{}",
+        game_settings.geometry.width, game_settings.geometry.height
+    );
+    println!("\t5: reach\t {}", game_settings.geometry.reach);
+    println!(
+        "\t6: distance hints {}",
+        on_off(game_settings.distance_hints)
+    );
+    println!("\t7: zmoves\t {}", on_off(game_settings.zmoves_allowed));
+    println!(
+        "\t8: spawn distance {}",
+        game_settings.geometry.spawn_distance
+    );
+    match game_settings.fog_radius {
+        Some(radius) => println!("\t9: fog\t\t you see {} cell(s) away", radius),
+        None => println!("\t9: fog\t\t off"),
+    }
+    println!(
+        "\t10: direction hints {}",
+        on_off(game_settings.direction_hints)
+    );
+    match game_settings.hint_noise {
+        Some(HintNoise {
+            spread,
+            noise_percent,
+            lie_percent,
+        }) => println!(
+            "\t11: hint noise\t ±{} {}% of the time, lies {}%",
+            spread, noise_percent, lie_percent
+        ),
+        None => println!("\t11: hint noise\t off"),
+    }
+    println!("\t12: palette\t {}", game_settings.palette);
+    println!("\n\tGame code\t {}", game_settings.to_code());
 
     println!("\nTo change a setting, please enter the corresponding number.");
     println!("To reset to default enter 'default' (or 'd')");
+    if !config.profiles.is_empty() {
+        let names: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+        println!(
+            "To load a profile enter 'profile' (or 'p'), yours are: {}",
+            names.join(", ")
+        );
+    }
+    println!("To save these settings as a profile enter 'save' (or 's')");
+    println!("To play the game of a friend enter 'code' (or 'g') and paste their game code");
     println!("To continue enter 'continue' (or 'c') or anything else")
 }
 
 /// simple method to print the list of awailable commands
-pub fn print_turn_command() -> () {
+pub fn print_turn_command() {
     println!("\n\tYour possible actions are:");
     println!("\t0: Move");
     println!("\t1: Search");
     println!("\t2: Quit");
+    println!("\t3: Export");
+    println!("\t4: Reach preview (on/off)");
+    println!("\t5: Sonar (o)");
+    println!("\t6: Compass (c)");
+    println!("\t7: Metal detector (d)");
+    println!("\t8: Candidates (on/off) (k)");
+    println!("\t9: Heatmap (off/distance/recency) (h)");
+    println!("\t10: Past searches (l)");
 
     println!("\nTo choose your action please enter the corresponding number or name.");
     println!("You can also enter the first letter of the wanted action.");
     println!("You can also move immediately by entering a Zmove.");
-    println!("To know more about zmoves, enter Zmove or z.")
+    println!("To know more about zmoves, enter Zmove or z.");
+    println!("You can chain zmoves and end with a search [e.g. '8,2 6,3 s' '8x2;6x3;s'].");
+    println!("On a big board you can pan the view with pan and a num pad direction [e.g. 'pan 8' 'p4'], pan alone centers it back on you.")
+}
+
+/// simple method to print the uses left of each item
+///
+/// # Arguments
+/// * `inventory` - the items of the player
+pub fn print_inventory(inventory: &Inventory) {
+    println!(
+        "Your items: {} sonar(s), {} compass(es), {} metal detector(s).",
+        inventory.count(Item::Sonar),
+        inventory.count(Item::Compass),
+        inventory.count(Item::MetalDetector)
+    );
+}
+
+/// simple method to tell what an item found
+///
+/// # Arguments
+/// * `result` - what the item told
+pub fn print_item_result(result: &ItemResult) {
+    match result {
+        ItemResult::Sonar(true) => println!(
+            "\nThe sonar pings, the treasure is within {} cells of you!",
+            Board::SONAR_RADIUS
+        ),
+        ItemResult::Sonar(false) => println!(
+            "\nThe sonar stays silent, the treasure is more than {} cells away.",
+            Board::SONAR_RADIUS
+        ),
+        ItemResult::Compass(Some(direction)) => {
+            println!("\nThe compass points {}.", direction)
+        }
+        ItemResult::Compass(None) => println!("\nThe compass spins, the treasure is right here!"),
+        ItemResult::MetalDetector(true) => {
+            println!("\nThe metal detector beeps, you dig the treasure out!")
+        }
+        ItemResult::MetalDetector(false) => {
+            println!("\nThe metal detector found nothing around you.")
+        }
+    }
+}
+
+/// simple method to print the zmoves allowed by the move set, before the zmove prompt
+///
+/// # Arguments
+/// * `move_set` - the move set of the game
+/// * `zmove_speeds` - the allowed speeds of each numpad direction, see `MoveSet::zmove_speeds`
+pub fn print_move_set_zmoves(move_set: &MoveSet, zmove_speeds: &[(u32, Vec<u32>)]) {
+    if zmove_speeds.is_empty() {
+        println!(
+            "\nNo zmove fits the {} move set, you have to use move.",
+            move_set
+        );
+        return;
+    }
+    println!("\nWith the {} move set, your zmoves are:", move_set);
+    for (direction, speeds) in zmove_speeds {
+        let speeds: Vec<String> = speeds.iter().map(|speed| speed.to_string()).collect();
+        println!("\tdirection {}: speed {}", direction, speeds.join(", "));
+    }
+}
+
+/// simple method to introduce the zmove rays, shown when the reach preview is on
+pub fn print_zmove_rays() {
+    println!("\nHere is where each zmove ends, the digit is the speed:");
+}
+
+/// simple method to tell the reach preview state after it was toggled
+///
+/// # Arguments
+/// * `show_reach` - if the reach preview is now on
+pub fn print_reach_toggled(show_reach: bool) {
+    match show_reach {
+        true => {
+            println!("\nThe reach preview is on, the cells you can move to are drawn with ':'.")
+        }
+        false => println!("\nThe reach preview is off."),
+    }
+}
+
+/// simple method to tell the candidates state after it was toggled
+///
+/// # Arguments
+/// * `show_candidates` - if the candidates are now drawn
+pub fn print_candidates_toggled(show_candidates: bool) {
+    match show_candidates {
+        true => println!(
+            "\nThe candidates are on, the cells that can hold the treasure show their odds from 1 to 9."
+        ),
+        false => println!("\nThe candidates are off."),
+    }
+}
+
+/// simple method to tell where the solver thinks the treasure is
+///
+/// # Arguments
+/// * `best` - the most likely cell and its odds, see `Board::best_candidate`
+pub fn print_best_candidate(best: Option<(Position, f64)>) {
+    match best {
+        Some((position, odds)) => println!(
+            "The treasure is most likely at {},{} ({:.1}%).",
+            position.x,
+            position.y,
+            odds * 100.0
+        ),
+        None => println!("No cell can hold the treasure anymore."),
+    }
+}
+
+/// simple method to tell how the searched cells are colored after the heatmap changed
+///
+/// # Arguments
+/// * `heatmap` - the heatmap now drawn
+pub fn print_heatmap_changed(heatmap: Heatmap) {
+    match heatmap {
+        Heatmap::Off => println!("\nThe heatmap is off, the searched cells are in your color."),
+        Heatmap::Distance => println!(
+            "\nThe searched cells are colored by their hint, red is the closest to the treasure, blue the farthest."
+        ),
+        Heatmap::Recency => println!(
+            "\nThe searched cells are colored by when you searched them, red is the latest, blue the oldest."
+        ),
+    }
+}
+
+/// simple method to print every past search, in order
+///
+/// # Arguments
+/// * `searches` - the searches and what they told, see `Tracker::searches`
+/// * `searched_count` - the cells the treasure isn't on, the items cleared some of them
+pub fn print_searches(searches: &[(Position, SearchRecord)], searched_count: usize) {
+    if searches.is_empty() {
+        println!("\nYou haven't searched anywhere yet.");
+        return;
+    }
+    println!("\nYour past searches:");
+    for (position, record) in searches {
+        match record.distance {
+            Some(distance) => println!(
+                "\tturn {}: {},{} - the treasure was {} tile(s) away",
+                record.turn, position.x, position.y, distance
+            ),
+            None => println!(
+                "\tturn {}: {},{} - no hint",
+                record.turn, position.x, position.y
+            ),
+        }
+    }
+    println!("You know the treasure isn't on {} cell(s).", searched_count);
+}
+
+/// simple method to tell where the view is after a pan
+///
+/// # Arguments
+/// * `center` - the cell now in the middle of the view, see `Board::view_center`
+pub fn print_view_panned(center: &Position) {
+    println!(
+        "\nThe view is centered on {},{}, enter 'pan' to center it back on you.",
+        center.x, center.y
+    );
+}
+
+/// simple method to tell a pan does nothing, the board fits in the view
+pub fn print_view_whole() {
+    println!("\nThe whole board is already drawn, there is nothing to pan.");
+}
+
+/// simple method to introduce the preview of a zmove chain
+pub fn print_sequence_preview() {
+    println!("\nHere is the path of your zmoves, '+' is where you will end:");
+}
+
+/// simple method to print the custom key bindings, nothing is printed without any
+///
+/// # Arguments
+/// * `bindings` - the loaded bindings
+pub fn print_bindings(bindings: &Bindings) {
+    if bindings.keys().is_empty() {
+        return;
+    }
+    println!("Your bindings are:");
+    for (key, text) in bindings.keys() {
+        println!("\t{}: {}", key, text);
+    }
+}
+
+/// simple method to tell the bindings file was refused
+///
+/// # Arguments
+/// * `path` - the bindings file
+/// * `reason` - what is wrong in it
+pub fn print_bindings_error(path: &str, reason: &str) {
+    println!(
+        "Your bindings in '{}' were ignored, {}.\nOnly the usual commands will work.",
+        path, reason
+    );
+}
+
+/// simple method to tell a profile of the configuration file can't be loaded
+///
+/// # Arguments
+/// * `path` - the configuration file
+/// * `warning` - which profile and what is wrong in it, see `Config::warnings`
+pub fn print_profile_warning(path: &str, warning: &str) {
+    println!(
+        "In your configuration '{}', the {} so it was skipped.",
+        path, warning
+    );
+}
+
+/// simple method to tell the configuration file was refused
+///
+/// # Arguments
+/// * `path` - the configuration file
+/// * `reason` - what is wrong in it
+pub fn print_config_error(path: &str, reason: &str) {
+    println!(
+        "Your configuration in '{}' was ignored, {}.\nThe game starts with the default settings.",
+        path, reason
+    );
+}
+
+/// simple method to tell a profile was saved
+///
+/// # Arguments
+/// * `name` - the name of the profile
+/// * `path` - the configuration file
+pub fn print_profile_saved(name: &str, path: &str) {
+    println!(
+        "The profile '{}' was saved in '{}', the next games will start with it.",
+        name, path
+    );
 }
 
 /// simple method to print the final message
@@ -61,13 +368,71 @@ pub fn print_end_screen() {
     println!("If you don't the game will stop and you'll have your terminal back.");
 }
 
+/// simple method to print the start of a daily challenge
+///
+/// # Arguments
+/// * `date` - the day of the challenge
+/// * `played` - the recorded result of that day, if it was already played
+pub fn print_daily_intro(date: NaiveDate, played: Option<&DailyResult>) {
+    println!(
+        "
+    #################################\n
+    #                               #\n
+    #   Daily challenge {}  #\n
+    #                               #\n
+    #################################
+    ",
+        date
+    );
+    println!("Everybody plays the same board on this day, the settings can't be changed.");
+    if let Some(result) = played {
+        println!(
+            "You already played this day ({}), this game won't be recorded.",
+            daily_result_text(result)
+        );
+    }
+}
+
+/// the result of a daily challenge in a few words
+fn daily_result_text(result: &DailyResult) -> String {
+    match result.won {
+        true => format!("won in {} turns", result.turns),
+        false => format!("lost after {} turns", result.turns),
+    }
+}
+
+/// simple method to tell a daily challenge of a chosen day isn't recorded
+pub fn print_daily_not_recorded() {
+    println!("\nThis day was chosen, the game isn't recorded in your daily history.");
+}
+
+/// simple method to print the streaks and the last daily challenges
+///
+/// # Arguments
+/// * `history` - every recorded daily challenge
+/// * `today` - the day of the challenge just played
+pub fn print_daily_history(history: &DailyHistory, today: NaiveDate) {
+    println!(
+        "\nYour streak is {} day(s), your best one is {} day(s).",
+        history.streak(today),
+        history.best_streak()
+    );
+    println!("Your last daily challenges:");
+    for result in history.days.iter().rev().take(Board::DAILY_HISTORY_SHOWN) {
+        println!("\t{}\t{}", result.date, daily_result_text(result));
+    }
+}
+
 /// a simple method to print the closing message
 pub fn print_goodbye() {
     println!("Have a nice day!");
 }
 
 /// a simple method to print the winner message
-pub fn print_win_screen() {
+///
+/// # Arguments
+/// * `seed` - the seed of the game, so the board can be played again
+pub fn print_win_screen(seed: u64) {
     println!(
         "
     #################################\n
@@ -77,6 +442,7 @@ pub fn print_win_screen() {
     #################################
     "
     );
+    println!("This board was the seed {}.", seed);
 }
 
 /// a simple method to print the nothing found message and to indicate the distance to the treasure
@@ -94,13 +460,81 @@ pub fn print_found_nothing(shortest_dist: u32) {
     println!("Adventure awaits.")
 }
 
+/// simple method to print the direction hint of a failed search
+///
+/// # Arguments
+/// * `direction` - the direction of the treasure, see `Board::treasure_direction`
+pub fn print_treasure_direction(direction: Direction) {
+    println!(
+        "Your compass needle shakes, the treasure is {} from here.",
+        direction
+    );
+}
+
+/// simple method to print a failed search when the distance hints are off
+pub fn print_found_nothing_without_hint() {
+    println!("You searched your current position but sadly found nothing.");
+    println!("You update the tracker, your compass stays silent in this game.");
+    println!("Adventure awaits.")
+}
+
+/// simple method to warn the player their color is hard to see, it was kept anyway
+///
+/// # Arguments
+/// * `warning` - what makes it hard to see, see `Palette::check_player_color`
+pub fn print_color_warning(warning: &str) {
+    println!("Careful, {}.", warning);
+}
+
+/// simple method to tell the player their color changed with the palette
+///
+/// # Arguments
+/// * `color` - the color of the new palette
+pub fn print_palette_color(color: &Color) {
+    println!(
+        "Your color is now {:?}, it's easy to see on this palette.",
+        color
+    );
+}
+
+/// simple method to remind the player the hints are blurred in this game
+pub fn print_hint_noise_warning() {
+    println!("But the compass is shaky in this game, the distance may be off or even a lie.");
+}
+
+/// simple method to offer the export of the final board and of the game animation
+pub fn print_export_offer() {
+    println!("\nDo you want to export the final board and an animation of your game?");
+    println!("If you found the treasure, it will be revealed.");
+}
+
+/// simple method to tell where the exported images were written
+///
+/// # Arguments
+/// * `svg_path` - the path of the written svg file
+/// * `png_path` - the path of the written png file
+pub fn print_export_done(svg_path: &str, png_path: &str) {
+    println!(
+        "Your board has been exported to {} and {}.",
+        svg_path, png_path
+    );
+}
+
+/// simple method to tell where the exported animation was written
+///
+/// # Arguments
+/// * `gif_path` - the path of the written gif file
+pub fn print_timeline_export_done(gif_path: &str) {
+    println!("Your game animation has been exported to {}.", gif_path);
+}
+
 /// simple method to print the message for the corrector
 /// In a real product, the correction shall be done automatically
 ///
 /// # Arguments
 /// * `oob_postion` - The out of bound position the user entered
 /// * `ib_position` - The corresponding modulated in bound position the user entered.
-pub fn print_special_corrector_message(oob_position: &Position, ib_position: &Position) -> () {
+pub fn print_special_corrector_message(oob_position: &Position, ib_position: &Position) {
     println!(
         "Howdy, you entered a out of bound position: {}.",
         oob_position
@@ -114,25 +548,93 @@ pub fn print_special_corrector_message(oob_position: &Position, ib_position: &Po
 
 /// Paints the given tile in the given color for the board print function
 ///
-/// Tile is a char and not a str because long str are ugly when in board.
+/// Tile is a single grapheme and not a str because long str are ugly when in board.
+/// the padding goes by its display width and not its chars, so a wide emoji keeps the grid aligned
 ///
 /// # Arguments
-/// * `buffer` - a mutable reference to the termcolor::Buffer that will be written to
+/// * `buffer` - a mutable reference to the colored writer, a termcolor::Buffer when printing
 /// * `color` - a termcolor::Color that will be used for the text written in the buffer
-/// * `tile` - the char representing the tile that will be written once in the buffer
+/// * `tile` - the grapheme representing the tile that will be written once in the buffer
+/// * `cell_width` - the columns of a cell, the tile is centered in them, it's at least the tile width
 ///
 /// # Returns
 /// * A Result containing either a "void" or an error
-fn tile_painter(buffer: &mut termcolor::Buffer, color: Color, tile: char) -> io::Result<()> {
+fn tile_painter<W: WriteColor>(
+    buffer: &mut W,
+    color: Color,
+    tile: &Tile,
+    cell_width: usize,
+) -> io::Result<()> {
+    let padding = cell_width.saturating_sub(tile.width());
     buffer.set_color(ColorSpec::new().set_fg(Some(color)))?;
-    write!(buffer, "{:^3}", tile)?;
+    write!(
+        buffer,
+        "{:left$}{}{:right$}",
+        "",
+        tile,
+        "",
+        left = padding / 2,
+        right = padding - padding / 2
+    )?;
     Ok(())
 }
 
 impl Board {
+    /// Gives the tile and its color for a given position of the board
+    ///
+    /// This is the single place where the look of a tile is decided,
+    /// the terminal print and the image exports both rely on it
+    ///
+    /// # Arguments
+    /// * `position` - the in board position of the tile
+    /// * `reveal_treasure` - if the treasure should be drawn, it's hidden during a game
+    ///
+    /// # Returns
+    /// * `(Tile, Color)` - the tile and the color it should be painted in
+    pub(super) fn get_painted_tile(
+        &self,
+        position: &Position,
+        reveal_treasure: bool,
+    ) -> (Tile, Color) {
+        // once revealed the treasure is drawn over the player, who is standing on it after a win
+        if reveal_treasure && *position == self.treasure_coordinates {
+            (Tile::from(Board::TREASURE_TILE), Board::TREASURE_COLOR)
+        } else if *position == self.player_coordinates {
+            (self.player_tile.clone(), self.player_color)
+        } else if self.tracker.is_searched(position) {
+            (
+                Tile::from(Board::WATER_TILE),
+                self.palette.searched(self.player_color),
+            )
+        } else {
+            (Tile::from(Board::WATER_TILE), self.palette.water())
+        }
+    }
+
+    /// Tells if a cell is drawn, with the fog only the ones around the player are
+    /// the searched cells stay visible, the player remembers them
+    ///
+    /// # Arguments
+    /// * `position` - the in board position of the cell
+    ///
+    /// # Returns
+    /// * `bool` - false if the cell is hidden by the fog
+    pub(super) fn is_visible(&self, position: &Position) -> bool {
+        match self.fog_radius {
+            None => true,
+            Some(radius) => {
+                self.tracker.is_searched(position)
+                    || Position::get_shortest_dist(
+                        self.player_coordinates.get_xy_dists(position),
+                        &self.geometry,
+                    ) <= radius
+            }
+        }
+    }
+
     /// Prints the `Board` to `stdout`.
     ///
-    /// This functions requires definition of the `DEFAULT_BOARD_WIDTH`, `DEFAULT_BOARD_HEIGHT` and `BOARD_COLOR` constants
+    /// This functions requires the board `geometry` and the `BOARD_COLOR` constant
     ///
     /// # Returns
     ///
@@ -140,61 +642,450 @@ impl Board {
     /// Note: The actual definition of Write uses io::Result, which is just a synonym for Result<T, io::Error>.
     /// if an error is fished it's transmitted
     pub(super) fn print_game_board(&self) -> io::Result<()> {
+        let mut overlay: Vec<(Position, char, Color)> = self.heatmap_overlay();
+        if self.show_candidates {
+            overlay.extend(self.candidate_overlay());
+        }
+        if self.show_reach {
+            overlay.extend(self.reach_overlay());
+        }
+        self.print_game_board_with_overlay(&overlay)
+    }
+
+    /// Gives the overlay of the searched cells colored by the heatmap, see `Heatmap`
+    /// the colors go from the first of `HEATMAP_COLORS` for the closest or latest
+    /// to the last one for the farthest or oldest, the cells without a hint keep their color
+    ///
+    /// # Returns
+    /// * `Vec<(Position, char, Color)>` - the colored cells, empty if the heatmap is off, the player cell excluded
+    pub(super) fn heatmap_overlay(&self) -> Vec<(Position, char, Color)> {
+        let records = self
+            .tracker
+            .records()
+            .filter(|(position, _)| *position != self.player_coordinates);
+        // how far each cell is from the warmest one
+        let values: Vec<(Position, u32)> = match self.heatmap {
+            Heatmap::Off => return Vec::new(),
+            Heatmap::Distance => records
+                .filter_map(|(position, record)| Some((position, record.distance? - 1)))
+                .collect(),
+            Heatmap::Recency => records
+                .map(|(position, record)| (position, self.turn - record.turn))
+                .collect(),
+        };
+        let max = match self.heatmap {
+            Heatmap::Distance => {
+                Geometry::max_spawn_distance(self.geometry.width, self.geometry.height) - 1
+            }
+            _ => values.iter().map(|(_, value)| *value).max().unwrap_or(0),
+        };
+
+        let shades = Board::HEATMAP_COLORS.len() as u32 - 1;
+        values
+            .into_iter()
+            .map(|(position, value)| {
+                let shade = value.min(max) * shades / max.max(1);
+                (
+                    position,
+                    Board::WATER_TILE,
+                    Board::HEATMAP_COLORS[shade as usize],
+                )
+            })
+            .collect()
+    }
+
+    /// Gives the overlay of the cells in view that can hold the treasure, see `Board::candidate_weights`
+    /// each one shows its odds from 1 to 9, 9 is the most likely cell of the whole board
+    ///
+    /// # Returns
+    /// * `Vec<(Position, char, Color)>` - the candidates, in `CANDIDATE_COLOR`, the player cell excluded
+    pub(super) fn candidate_overlay(&self) -> Vec<(Position, char, Color)> {
+        let (total, best) = match self.candidate_summary() {
+            Some((total, _, best)) => (total, best / total),
+            None => return Vec::new(),
+        };
+        self.candidate_weights(total)
+            .into_iter()
+            .filter(|(position, _)| *position != self.player_coordinates)
+            .map(|(position, weight)| {
+                let shade = ((weight / best) * 9.0).ceil().clamp(1.0, 9.0) as u32;
+                (
+                    position,
+                    std::char::from_digit(shade, 10).unwrap_or(Board::PATH_TILE),
+                    Board::CANDIDATE_COLOR,
+                )
+            })
+            .collect()
+    }
+
+    /// Gives the overlay of a path, see `print_game_board_with_overlay`
+    ///
+    /// # Arguments
+    /// * `path` - the positions crossed in order, the last one is drawn as the end
+    ///
+    /// # Returns
+    /// * `Vec<(Position, char, Color)>` - the path tiles, in the player color
+    pub(super) fn path_overlay(&self, path: &[Position]) -> Vec<(Position, char, Color)> {
+        path.iter()
+            .enumerate()
+            .map(|(index, position)| match index == path.len() - 1 {
+                true => (position.clone(), Board::PATH_END_TILE, self.player_color),
+                false => (position.clone(), Board::PATH_TILE, self.player_color),
+            })
+            .collect()
+    }
+
+    /// Gives the overlay of every cell the player can move to, see `print_game_board_with_overlay`
+    /// the cells keep their color, so the searched ones are still visible
+    /// only the cells of the `viewport` are looked at, the others aren't drawn
+    ///
+    /// # Returns
+    /// * `Vec<(Position, char, Color)>` - the reachable cells, the player cell excluded
+    pub(super) fn reach_overlay(&self) -> Vec<(Position, char, Color)> {
+        let (columns, rows) = self.viewport();
+        columns
+            .iter()
+            .flat_map(|&x| rows.iter().map(move |&y| Position { x, y }))
+            .filter(|position| {
+                *position != self.player_coordinates
+                    && self
+                        .move_set
+                        .is_legal(&self.player_coordinates, position, &self.geometry)
+            })
+            .map(|position| {
+                let (_, color) = self.get_painted_tile(&position, false);
+                (position, Board::REACH_TILE, color)
+            })
+            .collect()
+    }
+
+    /// Gives the overlay of the zmove endpoints, each one shows its speed along its direction ray
+    ///
+    /// # Returns
+    /// * `Vec<(Position, char, Color)>` - the endpoints, in the player color
+    pub(super) fn zmove_rays_overlay(&self) -> Vec<(Position, char, Color)> {
+        let mut overlay: Vec<(Position, char, Color)> = Vec::new();
+        for (direction, speeds) in self.move_set.zmove_speeds(&self.geometry) {
+            for speed in speeds {
+                if let Ok(zmove) = Zmove::new(direction, speed) {
+                    let vector = zmove.get_vector();
+                    overlay.push((
+                        Board::coordinate_modulo(
+                            (
+                                self.player_coordinates.to_i64().0 + vector.0,
+                                self.player_coordinates.to_i64().1 + vector.1,
+                            ),
+                            &self.geometry,
+                        ),
+                        std::char::from_digit(speed, 10).unwrap_or(Board::PATH_TILE),
+                        self.player_color,
+                    ));
+                }
+            }
+        }
+        overlay
+    }
+
+    /// Gives the cell in the middle of the view, the player unless the view was panned
+    ///
+    /// # Returns
+    /// * `Position` - the in board center of the view
+    pub(super) fn view_center(&self) -> Position {
+        let (x, y) = self.player_coordinates.to_i64();
+        Board::coordinate_modulo((x + self.pan.0, y + self.pan.1), &self.geometry)
+    }
+
+    /// Moves the view half a screen, it follows the player from there on
+    ///
+    /// # Arguments
+    /// * `direction` - where the view goes, `None` centers it back on the player
+    ///
+    /// # Returns
+    /// * `bool` - false if the whole board is drawn, there is nothing to pan then
+    pub(super) fn pan_view(&mut self, direction: Option<Direction>) -> bool {
+        let (width, height) = (self.geometry.width, self.geometry.height);
+        let (_, max_columns, max_rows) = self.layout();
+        if width <= max_columns && height <= max_rows {
+            self.pan = (0, 0);
+            return false;
+        }
+        self.pan = match direction {
+            None => (0, 0),
+            Some(direction) => {
+                let (x, y) = Direction::get_i64_pair_from_direction(&direction);
+                (
+                    (self.pan.0 + x * (max_columns / 2) as i64).rem_euclid(width as i64),
+                    (self.pan.1 + y * (max_rows / 2) as i64).rem_euclid(height as i64),
+                )
+            }
+        };
+        true
+    }
+
+    /// the width of the row coordinates, they are right aligned on the widest one
+    fn label_width(&self) -> usize {
+        (self.geometry.height - 1).to_string().len().max(2)
+    }
+
+    /// Gives how the board is drawn in the `terminal`
+    /// the cells get narrower until the whole board fits, see `CELL_WIDTHS`, but never narrower than the player tile,
+    /// if it doesn't fit even then the cells stay wide and the board is cut to a `viewport`
+    /// without a known terminal the view is `VIEWPORT_WIDTH` by `VIEWPORT_HEIGHT` cells
+    ///
+    /// # Returns
+    /// * `(usize, u32, u32)` - the columns of a cell, then the most columns and rows drawn
+    pub(super) fn layout(&self) -> (usize, u32, u32) {
+        let (width, height) = (self.geometry.width, self.geometry.height);
+        let (columns, lines) = match self.terminal {
+            Some(size) => size,
+            None => {
+                return (
+                    Board::CELL_WIDTHS[0],
+                    Board::VIEWPORT_WIDTH,
+                    Board::VIEWPORT_HEIGHT,
+                )
+            }
+        };
+        // the row coordinates and the borders take some room
+        let room = columns.saturating_sub(self.label_width() as u32 + 3) as usize;
+        let (cell_width, max_columns) = match Board::CELL_WIDTHS
+            .iter()
+            .filter(|&&cell_width| cell_width >= self.player_tile.width())
+            .find(|&&cell_width| width as usize * cell_width <= room)
+        {
+            Some(&cell_width) => (cell_width, width),
+            None => (
+                Board::CELL_WIDTHS[0],
+                ((room / Board::CELL_WIDTHS[0]) as u32).max(Board::VIEWPORT_MIN_SIDE),
+            ),
+        };
+        let max_rows = match height + Board::BOARD_FRAME_LINES <= lines {
+            true => height,
+            // a cut board has its minimap under it
+            false => lines
+                .saturating_sub(Board::BOARD_FRAME_LINES + Board::MINIMAP_HEIGHT + 1)
+                .max(Board::VIEWPORT_MIN_SIDE),
+        };
+        (cell_width, max_columns, max_rows)
+    }
+
+    /// Gives the cells drawn on the terminal, the whole board if it fits in the `layout`,
+    /// else the window of cells around the `view_center`
+    /// the window wraps across the torus edges like the moves, so the center is always in the middle
+    ///
+    /// # Returns
+    /// * `(Vec<u32>, Vec<u32>)` - the drawn columns from the left and rows from the bottom
+    pub(super) fn viewport(&self) -> (Vec<u32>, Vec<u32>) {
+        let span = |center: u32, side: u32, max: u32| -> Vec<u32> {
+            if side <= max {
+                return (0..side).collect();
+            }
+            let start = center as i64 - (max / 2) as i64;
+            (0..max as i64)
+                .map(|offset| (start + offset).rem_euclid(side as i64) as u32)
+                .collect()
+        };
+        let center = self.view_center();
+        let (_, max_columns, max_rows) = self.layout();
+        (
+            span(center.x, self.geometry.width, max_columns),
+            span(center.y, self.geometry.height, max_rows),
+        )
+    }
+
+    /// Tells if only a part of the board is drawn, see `viewport`
+    pub(super) fn is_view_cut(&self) -> bool {
+        let (columns, rows) = self.viewport();
+        columns.len() < self.geometry.width as usize || rows.len() < self.geometry.height as usize
+    }
+
+    /// Prints the `Board` to `stdout` with some tiles replaced, see `write_game_board`
+    ///
+    /// # Arguments
+    /// * `overlay` - the replaced tiles and their color, if a position is given twice the last one is drawn
+    ///
+    /// # Returns
+    /// * A io::Result, if an error is fished it's transmitted
+    pub(super) fn print_game_board_with_overlay(
+        &self,
+        overlay: &[(Position, char, Color)],
+    ) -> io::Result<()> {
         let bufwtr = BufferWriter::stdout(ColorChoice::Always);
         let mut buffer = bufwtr.buffer();
+        self.write_game_board(&mut buffer, overlay)?;
+
+        // print function ends here, we restore the buffer color to the "normal one"
+        buffer.set_color(ColorSpec::new().set_fg(Some(Color::White)))?; // I don't know how I can find the default terminal color, so it's white now
+        bufwtr.print(&buffer)
+    }
+
+    /// Writes the `Board` with some tiles replaced, only the cells of the `viewport` are drawn
+    /// so the cost doesn't grow with the board size, the cells are as wide as the `layout` says
+    /// the cells hidden by the fog are drawn as unknown, the coordinates are always written
+    ///
+    /// # Arguments
+    /// * `buffer` - where the board is written
+    /// * `overlay` - the replaced tiles and their color, if a position is given twice the last one is drawn
+    ///
+    /// # Returns
+    /// * A io::Result, if an error is fished it's transmitted
+    pub(super) fn write_game_board<W: WriteColor>(
+        &self,
+        buffer: &mut W,
+        overlay: &[(Position, char, Color)],
+    ) -> io::Result<()> {
+        let (columns, rows) = self.viewport();
+        let (cell_width, _, _) = self.layout();
+        let label_width = self.label_width();
+        // the row coordinates, a space and the side
+        let gutter = label_width + 2;
+        let (top, bottom) = match cell_width {
+            3 => ("⎺-⎺".to_string(), "_⎽_".to_string()),
+            _ => ("⎺".repeat(cell_width), "⎽".repeat(cell_width)),
+        };
 
         // Top row
         buffer.set_color(ColorSpec::new().set_fg(Some(Board::BOARD_COLOR)))?;
-        write!(&mut buffer, "{:>4}", "⌜")?;
-        for _ in 0..Board::DEFAULT_BOARD_WIDTH {
-            write!(&mut buffer, "⎺-⎺")?;
+        write!(buffer, "{:>width$}", "⌜", width = gutter)?;
+        for _ in &columns {
+            write!(buffer, "{}", top)?;
         }
-        writeln!(&mut buffer, "⌝")?;
+        writeln!(buffer, "⌝")?;
 
         // Main grid
-        for y in (0..Board::DEFAULT_BOARD_HEIGHT).rev() {
-            write!(&mut buffer, "{:>2} ∣", y)?; // Side coordinates
-
-            for x in 0..Board::DEFAULT_BOARD_WIDTH {
-                //TODO dont forget to make the treasure invisble in the realese version
-                if x == self.player_coordinates.x && y == self.player_coordinates.y {
-                    tile_painter(&mut buffer, self.player_color, self.player_tile)?;
-                } else {
-                    let water_color: Color = match self.tracker[x as usize][y as usize] {
-                        true => self.player_color,
-                        false => Color::Blue,
-                    };
-
-                    tile_painter(
-                        &mut buffer,
-                        water_color,
-                        Board::WATER_TILE,
-                        // considering creating a painted_tile struct that encapsulate a "tile" in 2d videogame terms
-                    )?;
-                }
+        for &y in rows.iter().rev() {
+            write!(buffer, "{:>width$} ∣", y, width = label_width)?; // Side coordinates
+
+            for &x in &columns {
+                // the treasure stays invisible while playing
+                let position = Position { x, y };
+                let (tile, color) = match overlay.iter().rev().find(|(p, _, _)| *p == position) {
+                    Some((_, tile, color)) => (Tile::from(*tile), *color),
+                    None if !self.is_visible(&position) => {
+                        (Tile::from(Board::FOG_TILE), Board::FOG_COLOR)
+                    }
+                    None => self.get_painted_tile(&position, false),
+                };
+                tile_painter(buffer, color, &tile, cell_width)?;
                 // we dont forget to restore the buffer to white for the borders
                 buffer.set_color(ColorSpec::new().set_fg(Some(Board::BOARD_COLOR)))?;
             }
 
-            writeln!(&mut buffer, "∣")?; // Side column
+            writeln!(buffer, "∣")?; // Side column
         }
 
         // Bottom row
-        write!(&mut buffer, "{:>4}", "⌞")?;
-        for _ in 0..Board::DEFAULT_BOARD_WIDTH {
-            write!(&mut buffer, "_⎽_")?;
+        write!(buffer, "{:>width$}", "⌞", width = gutter)?;
+        for _ in &columns {
+            write!(buffer, "{}", bottom)?;
         }
-        writeln!(&mut buffer, "⌟")?;
+        writeln!(buffer, "⌟")?;
 
-        // Bottom coordinates
-        write!(&mut buffer, "{:4}", "")?;
-        for x in 0..Board::DEFAULT_BOARD_WIDTH {
-            write!(&mut buffer, "{:^3}", x)?;
+        // Bottom coordinates, only every few columns if they are wider than a cell
+        write!(buffer, "{:width$}", "", width = gutter)?;
+        let digits = (self.geometry.width - 1).to_string().len();
+        let step = (digits + 1).div_ceil(cell_width);
+        for (index, x) in columns.iter().enumerate().step_by(step) {
+            let room = step.min(columns.len() - index) * cell_width;
+            match (step, x.to_string()) {
+                (1, label) => write!(buffer, "{:^width$}", label, width = room)?,
+                (_, label) if label.len() <= room => {
+                    write!(buffer, "{:<width$}", label, width = room)?
+                }
+                _ => write!(buffer, "{:width$}", "", width = room)?,
+            }
         }
-        writeln!(&mut buffer)?;
+        writeln!(buffer)?;
 
-        // print function ends here, we restore the buffer color to the "normal one"
-        buffer.set_color(ColorSpec::new().set_fg(Some(Color::White)))?; // I don't know how I can find the default terminal color, so it's white now
-        return bufwtr.print(&buffer);
+        // the minimap tells where the view is on a board that isn't drawn whole
+        if self.is_view_cut() {
+            self.write_minimap(buffer, &columns, &rows)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the map of the whole board, each char is a block of cells
+    /// a block goes through `MINIMAP_TILES` as more of its cells are known, see `Tracker::records`
+    /// the player block shows their tile, the blocks in view are in the board color
+    ///
+    /// # Arguments
+    /// * `buffer` - where the minimap is written
+    /// * `columns` - the columns in view, see `viewport`
+    /// * `rows` - the rows in view
+    ///
+    /// # Returns
+    /// * A io::Result, if an error is fished it's transmitted
+    pub(super) fn write_minimap<W: WriteColor>(
+        &self,
+        buffer: &mut W,
+        columns: &[u32],
+        rows: &[u32],
+    ) -> io::Result<()> {
+        let (width, height) = (self.geometry.width, self.geometry.height);
+        let block_width = width.div_ceil(Board::MINIMAP_WIDTH);
+        let block_height = height.div_ceil(Board::MINIMAP_HEIGHT);
+        let blocks_x = width.div_ceil(block_width);
+        let blocks_y = height.div_ceil(block_height);
+
+        // the records are sparse, counting them is cheaper than going through the cells
+        let mut known = vec![0u64; (blocks_x * blocks_y) as usize];
+        for (position, _) in self.tracker.records() {
+            known[(position.y / block_height * blocks_x + position.x / block_width) as usize] += 1;
+        }
+        let mut in_view_x = vec![false; blocks_x as usize];
+        for x in columns {
+            in_view_x[(x / block_width) as usize] = true;
+        }
+        let mut in_view_y = vec![false; blocks_y as usize];
+        for y in rows {
+            in_view_y[(y / block_height) as usize] = true;
+        }
+
+        let gutter = self.label_width() + 2;
+        buffer.set_color(ColorSpec::new().set_fg(Some(Board::BOARD_COLOR)))?;
+        writeln!(
+            buffer,
+            "{:width$}minimap, a char is {}x{} cells, from '{}' unexplored to '{}' fully known",
+            "",
+            block_width,
+            block_height,
+            Board::MINIMAP_TILES[0],
+            Board::MINIMAP_TILES[Board::MINIMAP_TILES.len() - 1],
+            width = gutter
+        )?;
+        for block_y in (0..blocks_y).rev() {
+            write!(buffer, "{:width$}", "", width = gutter)?;
+            for block_x in 0..blocks_x {
+                let cells = (block_width.min(width - block_x * block_width) as u64)
+                    * (block_height.min(height - block_y * block_height) as u64);
+                let count = known[(block_y * blocks_x + block_x) as usize];
+                let shade = match count {
+                    0 => 0,
+                    _ if count == cells => Board::MINIMAP_TILES.len() - 1,
+                    _ => 1 + (count * 2 / cells) as usize,
+                };
+                let is_player = self.player_coordinates.x / block_width == block_x
+                    && self.player_coordinates.y / block_height == block_y;
+                // a char of the minimap is one column, a wide player tile would push the row
+                let (tile, color) = match is_player {
+                    true if self.player_tile.width() == 1 => {
+                        (self.player_tile.to_string(), self.player_color)
+                    }
+                    true => (Board::DEFAULT_PLAYER_TILE.to_string(), self.player_color),
+                    false if in_view_x[block_x as usize] && in_view_y[block_y as usize] => {
+                        (Board::MINIMAP_TILES[shade].to_string(), Board::BOARD_COLOR)
+                    }
+                    false => (
+                        Board::MINIMAP_TILES[shade].to_string(),
+                        self.palette.water(),
+                    ),
+                };
+                buffer.set_color(ColorSpec::new().set_fg(Some(color)))?;
+                write!(buffer, "{}", tile)?;
+            }
+            buffer.set_color(ColorSpec::new().set_fg(Some(Board::BOARD_COLOR)))?;
+            writeln!(buffer)?;
+        }
+        Ok(())
     }
 }
@@ -1,9 +1,216 @@
+use std::fmt;
+
+/// The context carried by most `BoardError`
+/// Only the message is mandatory, the rest is filled when it is known
+///
+/// # Attributes
+/// * `message` - the human readable explanation
+/// * `input` - the offending input, as the user typed it
+/// * `column` - the char index in `input` of the bad token
+/// * `expected` - the inclusive range the value should have been in
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct ErrorContext {
+    pub message: String,
+    pub input: Option<String>,
+    pub column: Option<usize>,
+    pub expected: Option<(i64, i64)>,
+}
+
+impl ErrorContext {
+    /// builds a context with only a message
+    ///
+    /// # Arguments
+    /// * `message` - the human readable explanation
+    pub fn new(message: &str) -> ErrorContext {
+        ErrorContext {
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// adds the offending input to the context
+    pub fn with_input(mut self, input: &str) -> ErrorContext {
+        self.input = Some(input.to_string());
+        self
+    }
+
+    /// adds the char index of the bad token in the input
+    pub fn at_column(mut self, column: usize) -> ErrorContext {
+        self.column = Some(column);
+        self
+    }
+
+    /// adds the inclusive range the value should have been in
+    pub fn expecting(mut self, min: i64, max: i64) -> ErrorContext {
+        self.expected = Some((min, max));
+        self
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    /// the message, the expected range if any, then the input with a caret under the bad token
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some((min, max)) = self.expected {
+            write!(f, " [expected {} to {}]", min, max)?;
+        }
+        if let (Some(input), Some(column)) = (&self.input, self.column) {
+            write!(f, "\n\t{}\n\t{}^", input, " ".repeat(column))?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum BoardError {
-    InvalidMove(String),     // when there is a move but is not a legal one
-    InvalidCommand(String),  // when there is word but is not a command
-    InvalidFormat(String),   // when the regex checks fails
-    FailedParse(String),     // when the value couldn't be parsed
-    Not2Dimensional(usize),  // when the number of dimension isn't 2
-    TooManyArguments(usize), // when there are too many arguments
+    InvalidMove(ErrorContext),    // when there is a move but is not a legal one
+    InvalidCommand(ErrorContext), // when there is word but is not a command
+    InvalidFormat(ErrorContext),  // when the regex checks fails
+    FailedParse(ErrorContext),    // when the value couldn't be parsed
+    Not2Dimensional(usize),       // when the number of dimension isn't 2
+    TooManyArguments(usize),      // when there are too many arguments
+}
+
+impl BoardError {
+    /// the stable code of each variant, used by the json output
+    /// those must never change, tools rely on them
+    ///
+    /// # Returns
+    /// * `&str` - the snake case code of the variant
+    pub fn code(&self) -> &'static str {
+        match self {
+            BoardError::InvalidMove(_) => "invalid_move",
+            BoardError::InvalidCommand(_) => "invalid_command",
+            BoardError::InvalidFormat(_) => "invalid_format",
+            BoardError::FailedParse(_) => "failed_parse",
+            BoardError::Not2Dimensional(_) => "not_2_dimensional",
+            BoardError::TooManyArguments(_) => "too_many_arguments",
+        }
+    }
+
+    /// the stable numeric code of each variant, shown to the player as `E01`, `E02`...
+    /// like `code`, those must never change
+    ///
+    /// # Returns
+    /// * `u16` - the numeric code of the variant
+    pub fn numeric_code(&self) -> u16 {
+        match self {
+            BoardError::InvalidMove(_) => 1,
+            BoardError::InvalidCommand(_) => 2,
+            BoardError::InvalidFormat(_) => 3,
+            BoardError::FailedParse(_) => 4,
+            BoardError::Not2Dimensional(_) => 5,
+            BoardError::TooManyArguments(_) => 6,
+        }
+    }
+
+    /// the context of the error, the dimension errors only carry a count
+    ///
+    /// # Returns
+    /// * `Some(&ErrorContext)` - for the variants that have one
+    /// * `None` - for `Not2Dimensional` and `TooManyArguments`
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            BoardError::InvalidMove(context)
+            | BoardError::InvalidCommand(context)
+            | BoardError::InvalidFormat(context)
+            | BoardError::FailedParse(context) => Some(context),
+            BoardError::Not2Dimensional(_) | BoardError::TooManyArguments(_) => None,
+        }
+    }
+
+    /// adds where the error happened to its context, if it has one
+    ///
+    /// # Arguments
+    /// * `input` - the whole offending input
+    /// * `column` - the char index of the bad token in `input`
+    ///
+    /// # Returns
+    /// * `BoardError` - the same error, located
+    pub fn located(self, input: &str, column: usize) -> BoardError {
+        match self {
+            BoardError::InvalidMove(context) => {
+                BoardError::InvalidMove(context.with_input(input).at_column(column))
+            }
+            BoardError::InvalidCommand(context) => {
+                BoardError::InvalidCommand(context.with_input(input).at_column(column))
+            }
+            BoardError::InvalidFormat(context) => {
+                BoardError::InvalidFormat(context.with_input(input).at_column(column))
+            }
+            BoardError::FailedParse(context) => {
+                BoardError::FailedParse(context.with_input(input).at_column(column))
+            }
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardError::InvalidMove(context)
+            | BoardError::InvalidCommand(context)
+            | BoardError::InvalidFormat(context)
+            | BoardError::FailedParse(context) => write!(f, "{}", context),
+            BoardError::Not2Dimensional(u) => {
+                write!(f, "Your value had {} dimension(s) instead of 2", u)
+            }
+            BoardError::TooManyArguments(u) => write!(f, "{} is too many elements", u),
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_context() {
+        assert_eq!(ErrorContext::new("bad").to_string(), "bad");
+        assert_eq!(
+            ErrorContext::new("bad").expecting(1, 4).to_string(),
+            "bad [expected 1 to 4]"
+        );
+        // without a column, the input isn't shown
+        assert_eq!(
+            ErrorContext::new("bad").with_input("3,4").to_string(),
+            "bad"
+        );
+        assert_eq!(
+            ErrorContext::new("bad")
+                .with_input("3,x")
+                .at_column(2)
+                .to_string(),
+            "bad\n\t3,x\n\t  ^"
+        );
+    }
+
+    #[test]
+    fn display_dimensions() {
+        assert_eq!(
+            BoardError::Not2Dimensional(3).to_string(),
+            "Your value had 3 dimension(s) instead of 2"
+        );
+        assert_eq!(
+            BoardError::TooManyArguments(5).to_string(),
+            "5 is too many elements"
+        );
+    }
+
+    #[test]
+    fn numeric_codes_are_stable() {
+        let context = ErrorContext::new("");
+        assert_eq!(BoardError::InvalidMove(context.clone()).numeric_code(), 1);
+        assert_eq!(
+            BoardError::InvalidCommand(context.clone()).numeric_code(),
+            2
+        );
+        assert_eq!(BoardError::InvalidFormat(context.clone()).numeric_code(), 3);
+        assert_eq!(BoardError::FailedParse(context).numeric_code(), 4);
+        assert_eq!(BoardError::Not2Dimensional(1).numeric_code(), 5);
+        assert_eq!(BoardError::TooManyArguments(3).numeric_code(), 6);
+    }
 }
@@ -0,0 +1,583 @@
+use super::{Board, Color, GameEvent, Position, Tracker};
+use std::fs;
+use std::io;
+
+/// Gives the rgb triplet of a termcolor color
+///
+/// the named colors use the usual xterm palette, the ansi 256 colors are
+/// computed the same way terminals do it (16 base colors, a 6x6x6 cube then a gray ramp)
+///
+/// # Arguments
+/// * `color` - the termcolor::Color to convert
+///
+/// # Returns
+/// * `(u8, u8, u8)` - the red, green and blue components
+pub fn color_to_rgb(color: &Color) -> (u8, u8, u8) {
+    match color {
+        Color::Black => (0, 0, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Green => (0, 205, 0),
+        Color::Red => (205, 0, 0),
+        Color::Cyan => (0, 205, 205),
+        Color::Magenta => (205, 0, 205),
+        Color::Yellow => (205, 205, 0),
+        Color::White => (229, 229, 229),
+        Color::Rgb(r, g, b) => (*r, *g, *b),
+        Color::Ansi256(i) => ansi256_to_rgb(*i),
+        _ => (229, 229, 229), // termcolor may add colors one day, white is the safest
+    }
+}
+
+/// the ansi 256 color table, see `color_to_rgb`
+fn ansi256_to_rgb(i: u8) -> (u8, u8, u8) {
+    const BASE: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    match i {
+        0..=15 => BASE[i as usize],
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let cube = i - 16;
+            (level(cube / 36), level((cube / 6) % 6), level(cube % 6))
+        }
+        _ => {
+            let gray = 8 + (i - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// Escapes the xml special characters, the player tile can be anything
+fn escape_xml(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_string(),
+            '<' => "&lt;".to_string(),
+            '>' => "&gt;".to_string(),
+            '"' => "&quot;".to_string(),
+            '\'' => "&apos;".to_string(),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+/// Gives the 3x5 bitmap of the few glyphs the png export knows
+///
+/// each u8 is a row from top to bottom, the 3 lowest bits are the pixels from left to right
+/// the player tile can be any char, if it's not known here, it will be drawn as a full square
+fn glyph_bitmap(c: char) -> Option<[u8; 5]> {
+    match c {
+        '0' => Some([0b111, 0b101, 0b101, 0b101, 0b111]),
+        '1' => Some([0b010, 0b110, 0b010, 0b010, 0b111]),
+        '2' => Some([0b111, 0b001, 0b111, 0b100, 0b111]),
+        '3' => Some([0b111, 0b001, 0b111, 0b001, 0b111]),
+        '4' => Some([0b101, 0b101, 0b111, 0b001, 0b001]),
+        '5' => Some([0b111, 0b100, 0b111, 0b001, 0b111]),
+        '6' => Some([0b111, 0b100, 0b111, 0b101, 0b111]),
+        '7' => Some([0b111, 0b001, 0b010, 0b010, 0b010]),
+        '8' => Some([0b111, 0b101, 0b111, 0b101, 0b111]),
+        '9' => Some([0b111, 0b101, 0b111, 0b001, 0b111]),
+        '~' => Some([0b000, 0b000, 0b011, 0b110, 0b000]),
+        'X' => Some([0b101, 0b101, 0b010, 0b101, 0b101]),
+        _ => None,
+    }
+}
+
+/// A minimal rgb raster used to build the png export
+struct Raster {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Raster {
+    fn new(width: u32, height: u32, background: (u8, u8, u8)) -> Raster {
+        let mut pixels = Vec::with_capacity((width * height * 3) as usize);
+        for _ in 0..(width * height) {
+            pixels.extend_from_slice(&[background.0, background.1, background.2]);
+        }
+        Raster {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn fill_rect(&mut self, x: u32, y: u32, w: u32, h: u32, rgb: (u8, u8, u8)) {
+        for py in y..std::cmp::min(y + h, self.height) {
+            for px in x..std::cmp::min(x + w, self.width) {
+                let i = ((py * self.width + px) * 3) as usize;
+                self.pixels[i..i + 3].copy_from_slice(&[rgb.0, rgb.1, rgb.2]);
+            }
+        }
+    }
+
+    /// Draws a glyph centered on the given point, a full square if it's unknown or not a lone char
+    /// each bitmap pixel becomes a `scale` sized square
+    fn draw_glyph(&mut self, center: (u32, u32), c: Option<char>, scale: u32, rgb: (u8, u8, u8)) {
+        let left = center.0 - (3 * scale) / 2;
+        let top = center.1 - (5 * scale) / 2;
+        match c.and_then(glyph_bitmap) {
+            Some(rows) => {
+                for (row_index, row) in rows.iter().enumerate() {
+                    for col in 0..3 {
+                        if row & (0b100 >> col) != 0 {
+                            self.fill_rect(
+                                left + col * scale,
+                                top + row_index as u32 * scale,
+                                scale,
+                                scale,
+                                rgb,
+                            );
+                        }
+                    }
+                }
+            }
+            None => self.fill_rect(left, top, 3 * scale, 5 * scale, rgb),
+        }
+    }
+
+    /// Draws a number centered on the given point, used for the axes
+    fn draw_number(&mut self, center: (u32, u32), number: u32, scale: u32, rgb: (u8, u8, u8)) {
+        let digits: Vec<char> = number.to_string().chars().collect();
+        let glyph_step = 4 * scale; // 3 pixels and a space
+        let total_width = glyph_step * digits.len() as u32 - scale;
+        let first_center = center.0 - total_width / 2 + (3 * scale) / 2;
+        for (i, digit) in digits.iter().enumerate() {
+            self.draw_glyph(
+                (first_center + i as u32 * glyph_step, center.1),
+                Some(*digit),
+                scale,
+                rgb,
+            );
+        }
+    }
+
+    fn encode_png(&self) -> io::Result<Vec<u8>> {
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)?;
+        Ok(bytes)
+    }
+}
+
+impl Board {
+    /// Gives the pixel size of an exported image
+    ///
+    /// the layout is the same as `print_game_board`: a gutter on the left for the y axis,
+    /// a gutter at the bottom for the x axis, and the board itself with y going up
+    ///
+    /// # Returns
+    /// * `(u32, u32)` - the width and the height in pixels
+    fn export_dimensions(&self) -> (u32, u32) {
+        (
+            (self.geometry.width + 2) * Board::EXPORT_CELL_SIZE,
+            (self.geometry.height + 2) * Board::EXPORT_CELL_SIZE,
+        )
+    }
+
+    /// Gives the top left pixel of a tile in an exported image
+    ///
+    /// # Arguments
+    /// * `position` - the in board position of the tile
+    ///
+    /// # Returns
+    /// * `(u32, u32)` - the x and y pixel, y going down like in every image format
+    fn export_cell_origin(&self, position: &Position) -> (u32, u32) {
+        (
+            (position.x + 1) * Board::EXPORT_CELL_SIZE,
+            (self.geometry.height - position.y) * Board::EXPORT_CELL_SIZE,
+        )
+    }
+
+    /// Renders the board as a svg document
+    ///
+    /// # Arguments
+    /// * `reveal_treasure` - if the treasure should be drawn, it should only be after a win
+    ///
+    /// # Returns
+    /// * `String` - the whole svg document
+    pub(super) fn to_svg(&self, reveal_treasure: bool) -> String {
+        let cell = Board::EXPORT_CELL_SIZE;
+        let (width, height) = self.export_dimensions();
+        let rgb = |color: &Color| {
+            let (r, g, b) = color_to_rgb(color);
+            format!("rgb({},{},{})", r, g, b)
+        };
+        let board_color = rgb(&Board::BOARD_COLOR);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+            font-family=\"monospace\" font-size=\"{f}\" text-anchor=\"middle\" dominant-baseline=\"central\">\n",
+            w = width,
+            h = height,
+            f = cell * 2 / 3
+        );
+        svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"black\"/>\n");
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"{}\"/>\n",
+            cell,
+            cell,
+            self.geometry.width * cell,
+            self.geometry.height * cell,
+            board_color
+        ));
+
+        // Main grid
+        for y in 0..self.geometry.height {
+            for x in 0..self.geometry.width {
+                let position = Position { x, y };
+                let (tile, color) = self.get_painted_tile(&position, reveal_treasure);
+                let (left, top) = self.export_cell_origin(&position);
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>\n",
+                    left + cell / 2,
+                    top + cell / 2,
+                    rgb(&color),
+                    escape_xml(tile.as_str())
+                ));
+            }
+        }
+
+        // Side coordinates
+        for y in 0..self.geometry.height {
+            let (_, top) = self.export_cell_origin(&Position { x: 0, y });
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>\n",
+                cell / 2,
+                top + cell / 2,
+                board_color,
+                y
+            ));
+        }
+
+        // Bottom coordinates
+        for x in 0..self.geometry.width {
+            let (left, _) = self.export_cell_origin(&Position { x, y: 0 });
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>\n",
+                left + cell / 2,
+                height - cell / 2,
+                board_color,
+                x
+            ));
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Renders the board as a raster, shared by the png and the gif exports
+    ///
+    /// only a few glyphs are known by the rasterizer (see `glyph_bitmap`),
+    /// an unknown player tile, or one of several chars, is drawn as a full square of the player color
+    ///
+    /// # Arguments
+    /// * `reveal_treasure` - if the treasure should be drawn, it should only be after a win
+    ///
+    /// # Returns
+    /// * `Raster` - the rendered board
+    fn to_raster(&self, reveal_treasure: bool) -> Raster {
+        let cell = Board::EXPORT_CELL_SIZE;
+        let scale = cell / 8; // a glyph is 5 pixels high, this leaves a margin in the cell
+        let (width, height) = self.export_dimensions();
+        let board_rgb = color_to_rgb(&Board::BOARD_COLOR);
+        let mut raster = Raster::new(width, height, (0, 0, 0));
+
+        // the border, drawn as a full rectangle then hollowed out
+        raster.fill_rect(
+            cell - 1,
+            cell - 1,
+            self.geometry.width * cell + 2,
+            self.geometry.height * cell + 2,
+            board_rgb,
+        );
+        raster.fill_rect(
+            cell,
+            cell,
+            self.geometry.width * cell,
+            self.geometry.height * cell,
+            (0, 0, 0),
+        );
+
+        // Main grid
+        for y in 0..self.geometry.height {
+            for x in 0..self.geometry.width {
+                let position = Position { x, y };
+                let (tile, color) = self.get_painted_tile(&position, reveal_treasure);
+                let (left, top) = self.export_cell_origin(&position);
+                raster.draw_glyph(
+                    (left + cell / 2, top + cell / 2),
+                    tile.as_char(),
+                    scale,
+                    color_to_rgb(&color),
+                );
+            }
+        }
+
+        // Side and bottom coordinates
+        for y in 0..self.geometry.height {
+            let (_, top) = self.export_cell_origin(&Position { x: 0, y });
+            raster.draw_number((cell / 2, top + cell / 2), y, 2, board_rgb);
+        }
+        for x in 0..self.geometry.width {
+            let (left, _) = self.export_cell_origin(&Position { x, y: 0 });
+            raster.draw_number((left + cell / 2, height - cell / 2), x, 2, board_rgb);
+        }
+
+        raster
+    }
+
+    /// Renders the board as a png image
+    ///
+    /// # Arguments
+    /// * `reveal_treasure` - if the treasure should be drawn, it should only be after a win
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - the bytes of the png file
+    /// * `Err` - if the png encoding failed
+    pub(super) fn to_png(&self, reveal_treasure: bool) -> io::Result<Vec<u8>> {
+        self.to_raster(reveal_treasure).encode_png()
+    }
+
+    /// Replays the game history and renders it as an animated gif
+    ///
+    /// there is one frame per event: the start, each move, each search and each item used,
+    /// a searched cell lights up in the player color like on the tracker
+    /// if the game was won, the last frame reveals the treasure and stays longer
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - the bytes of the gif file
+    /// * `Err` - if the gif encoding failed
+    pub(super) fn to_gif(&self) -> io::Result<Vec<u8>> {
+        let (width, height) = self.export_dimensions();
+        let mut bytes: Vec<u8> = Vec::new();
+        {
+            let mut encoder = gif::Encoder::new(&mut bytes, width as u16, height as u16, &[])
+                .map_err(io::Error::other)?;
+            encoder
+                .set_repeat(gif::Repeat::Infinite)
+                .map_err(io::Error::other)?;
+
+            // the replay starts from a blank tracker and follows the history
+            let mut replay = self.clone();
+            replay.tracker = Tracker::new(&self.geometry);
+
+            for (i, event) in self.history.iter().enumerate() {
+                match event {
+                    GameEvent::Start(position) | GameEvent::Move(position) => {
+                        replay.player_coordinates = position.clone()
+                    }
+                    // only the searched cells are drawn, the hints aren't needed
+                    GameEvent::Search(position) => replay.tracker.mark_cleared(position, 0),
+                    GameEvent::Scan(positions) => {
+                        for position in positions {
+                            replay.tracker.mark_cleared(position, 0)
+                        }
+                    }
+                }
+
+                let is_last_frame = i == self.history.len() - 1;
+                let raster = replay.to_raster(is_last_frame && self.is_treasure_found);
+                let mut frame =
+                    gif::Frame::from_rgb_speed(width as u16, height as u16, &raster.pixels, 10);
+                frame.delay = match is_last_frame {
+                    true => Board::EXPORT_LAST_FRAME_DELAY,
+                    false => Board::EXPORT_FRAME_DELAY,
+                };
+                encoder.write_frame(&frame).map_err(io::Error::other)?;
+            }
+        } // the encoder is dropped here, which writes the gif trailer
+        Ok(bytes)
+    }
+
+    /// Refuses the boards too big to be drawn in an image, see `EXPORT_MAX_SIDE`
+    ///
+    /// # Returns
+    /// * `Ok(())` - if the board can be exported
+    /// * `Err` - of kind `InvalidInput`, telling the biggest board
+    fn check_export_size(&self) -> io::Result<()> {
+        match self.geometry.width.max(self.geometry.height) > Board::EXPORT_MAX_SIDE {
+            true => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "the board is too big to be drawn, at most {0}x{0} boards are exported",
+                    Board::EXPORT_MAX_SIDE
+                ),
+            )),
+            false => Ok(()),
+        }
+    }
+
+    /// Writes the board to `EXPORT_SVG_PATH` and `EXPORT_PNG_PATH`
+    ///
+    /// # Arguments
+    /// * `reveal_treasure` - if the treasure should be drawn, it should only be after a win
+    ///
+    /// # Returns
+    /// * `Ok(())` - if both files were written
+    /// * `Err` - if the board is too big, or if the encoding or the writing failed
+    pub(super) fn export_board(&self, reveal_treasure: bool) -> io::Result<()> {
+        self.check_export_size()?;
+        fs::write(Board::EXPORT_SVG_PATH, self.to_svg(reveal_treasure))?;
+        fs::write(Board::EXPORT_PNG_PATH, self.to_png(reveal_treasure)?)
+    }
+
+    /// Writes the game animation to `EXPORT_GIF_PATH`
+    ///
+    /// # Returns
+    /// * `Ok(())` - if the file was written
+    /// * `Err` - if the board is too big, or if the encoding or the writing failed
+    pub(super) fn export_timeline(&self) -> io::Result<()> {
+        self.check_export_size()?;
+        fs::write(Board::EXPORT_GIF_PATH, self.to_gif()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{GameSettings, Palette, Tile, Zmove};
+    use super::*;
+
+    /// decodes a png and gives its dimensions and its rgb pixels
+    fn decode_png(bytes: &[u8]) -> (u32, u32, Vec<u8>) {
+        let decoder = png::Decoder::new(bytes);
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        (info.width, info.height, pixels)
+    }
+
+    #[test]
+    fn color_to_rgb_values() {
+        assert_eq!(color_to_rgb(&Color::Black), (0, 0, 0));
+        assert_eq!(color_to_rgb(&Color::Rgb(1, 2, 3)), (1, 2, 3));
+        assert_eq!(color_to_rgb(&Color::Ansi256(1)), color_to_rgb(&Color::Red));
+        assert_eq!(color_to_rgb(&Color::Ansi256(16)), (0, 0, 0));
+        assert_eq!(color_to_rgb(&Color::Ansi256(231)), (255, 255, 255));
+        assert_eq!(color_to_rgb(&Color::Ansi256(232)), (8, 8, 8));
+    }
+
+    #[test]
+    fn svg_hides_treasure_until_revealed() {
+        let test_board = Board::new(GameSettings::get_default_settings());
+        let treasure_text = format!(">{}</text>", Board::TREASURE_TILE);
+
+        let hidden = test_board.to_svg(false);
+        assert!(hidden.starts_with("<svg"));
+        assert!(hidden.trim_end().ends_with("</svg>"));
+        assert!(hidden.contains(&format!(">{}</text>", Board::DEFAULT_PLAYER_TILE)));
+        assert!(!hidden.contains(&treasure_text));
+
+        assert!(test_board.to_svg(true).contains(&treasure_text));
+    }
+
+    #[test]
+    fn svg_escapes_player_tile() {
+        let mut game_settings = GameSettings::get_default_settings();
+        game_settings.player_tile = Tile::from('<');
+        let svg = Board::new(game_settings).to_svg(false);
+        assert!(svg.contains(">&lt;</text>"));
+    }
+
+    #[test]
+    fn png_has_board_layout() {
+        let test_board = Board::new(GameSettings::get_default_settings());
+        let (width, height, pixels) = decode_png(&test_board.to_png(false).unwrap());
+        assert_eq!((width, height), test_board.export_dimensions());
+
+        // the center of the player tile is painted in the player color
+        let (left, top) = test_board.export_cell_origin(&test_board.player_coordinates);
+        let center =
+            (top + Board::EXPORT_CELL_SIZE / 2) * width + left + Board::EXPORT_CELL_SIZE / 2;
+        let i = (center * 3) as usize;
+        let (r, g, b) = color_to_rgb(&Board::DEFAULT_PLAYER_COLOR);
+        assert_eq!(pixels[i..i + 3], [r, g, b]);
+    }
+
+    #[test]
+    fn gif_has_one_frame_per_event() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.search_player_position();
+        test_board.zmove_logic(Zmove::new(8, 1).unwrap());
+        test_board.search_player_position();
+        assert_eq!(test_board.history.len(), 4);
+
+        let gif_bytes = test_board.to_gif().unwrap();
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(gif_bytes.as_slice())
+            .unwrap();
+        let mut frame_count = 0;
+        let mut last_delay = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            frame_count += 1;
+            last_delay = frame.delay;
+        }
+        assert_eq!(frame_count, test_board.history.len());
+        assert_eq!(last_delay, Board::EXPORT_LAST_FRAME_DELAY);
+    }
+
+    #[test]
+    fn revealed_treasure_is_drawn_over_player() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        let treasure = test_board.treasure_coordinates.clone();
+        test_board.set_player_coordinates(treasure.to_i64());
+        assert!(test_board.search_player_position());
+        assert_eq!(
+            test_board.get_painted_tile(&treasure, true),
+            (Tile::from(Board::TREASURE_TILE), Board::TREASURE_COLOR)
+        );
+        assert_eq!(
+            test_board.get_painted_tile(&treasure, false),
+            (
+                Tile::from(Board::DEFAULT_PLAYER_TILE),
+                Board::DEFAULT_PLAYER_COLOR
+            )
+        );
+    }
+
+    #[test]
+    fn palettes_recolor_the_water_and_the_searches() {
+        let mut settings = GameSettings::get_default_settings();
+        settings.palette = Palette::Protanopia;
+        settings.player_color = Palette::Protanopia.player_color();
+        let mut test_board = Board::new(settings);
+        let searched = test_board.player_coordinates.clone();
+        test_board.search_player_position();
+        test_board.set_player_coordinates((searched.x as i64 + 1, searched.y as i64));
+
+        let water = Position {
+            x: searched.x,
+            y: (searched.y + 2) % test_board.geometry.height,
+        };
+        assert_eq!(
+            test_board.get_painted_tile(&water, false).1,
+            Palette::Protanopia.water()
+        );
+        assert_eq!(
+            test_board.get_painted_tile(&searched, false).1,
+            Palette::Protanopia.searched(Palette::Protanopia.player_color())
+        );
+    }
+}
@@ -0,0 +1,443 @@
+use super::{
+    Board, BoardError, Color, ErrorContext, FromStr, GameSettings, Geometry, HintNoise, MoveSet,
+    Palette, Tile,
+};
+use std::convert::TryFrom;
+
+/// the named colors, in the order of their tag in a game code
+const NAMED_COLORS: [Color; 8] = [
+    Color::Black,
+    Color::Blue,
+    Color::Green,
+    Color::Red,
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::White,
+];
+
+/// the named move sets, in the order of their tag in a game code, the custom one comes after
+const NAMED_MOVE_SETS: [MoveSet; 5] = [
+    MoveSet::Classic,
+    MoveSet::King,
+    MoveSet::Knight,
+    MoveSet::Rook,
+    MoveSet::Bishop,
+];
+
+/// the error of every code that has the right chars but not the right content
+fn damaged_code(code: &str) -> BoardError {
+    BoardError::InvalidFormat(
+        ErrorContext::new("This game code is damaged, check it was copied whole").with_input(code),
+    )
+}
+
+/// reads the bytes of a game code in order
+struct CodeReader<'a> {
+    bytes: &'a [u8],
+    code: &'a str,
+}
+
+impl<'a> CodeReader<'a> {
+    fn byte(&mut self) -> Result<u8, BoardError> {
+        let (first, rest) = self
+            .bytes
+            .split_first()
+            .ok_or_else(|| damaged_code(self.code))?;
+        self.bytes = rest;
+        Ok(*first)
+    }
+
+    /// a LEB128 number, 7 bits per byte, the high bit tells if another byte follows
+    fn varint(&mut self) -> Result<u64, BoardError> {
+        let mut value: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(damaged_code(self.code))
+    }
+
+    /// a signed number, zigzag encoded so the small negative ones stay short
+    fn signed(&mut self) -> Result<i64, BoardError> {
+        let value = self.varint()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+
+    fn u32(&mut self) -> Result<u32, BoardError> {
+        let value = self.varint()?;
+        u32::try_from(value).map_err(|_| damaged_code(self.code))
+    }
+}
+
+fn push_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn push_signed(bytes: &mut Vec<u8>, value: i64) {
+    push_varint(bytes, ((value << 1) ^ (value >> 63)) as u64);
+}
+
+/// a checksum where the order matters, so two swapped chars are caught too
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0u8, |sum, byte| sum.rotate_left(1) ^ byte)
+}
+
+impl GameSettings {
+    /// the shareable game code of these settings, pasting it in the settings menu gives the same board
+    ///
+    /// the settings are packed as bytes: the version, the seed, the color, the tile,
+    /// the move set, the board size, reach and spawn distance, the rule toggles, the fog radius, the hint noise,
+    /// the rest of a tile made of several chars, the palette, then a checksum
+    /// the bytes are written in Crockford's base 32, in groups of `GAME_CODE_GROUP` chars
+    ///
+    /// # Returns
+    /// * `String` - the game code, like `0810-0000-...`
+    pub fn to_code(&self) -> String {
+        let mut bytes: Vec<u8> = vec![Board::GAME_CODE_VERSION];
+        bytes.extend(self.seed.to_le_bytes()); // a random seed would take 10 bytes as a varint
+
+        match self.player_color {
+            Color::Ansi256(value) => bytes.extend([8, value]),
+            Color::Rgb(r, g, b) => bytes.extend([9, r, g, b]),
+            named => bytes.push(
+                NAMED_COLORS
+                    .iter()
+                    .position(|color| *color == named)
+                    .unwrap_or(7) as u8, // an unknown color is written white
+            ),
+        }
+        // the first char of the tile, the others are at the end so the codes of a lone char didn't change
+        let mut tile_chars = self.player_tile.as_str().chars();
+        push_varint(&mut bytes, tile_chars.next().map_or(0, |c| c as u64));
+        let tile_rest: Vec<char> = tile_chars.collect();
+
+        match &self.move_set {
+            MoveSet::Custom(offsets) => {
+                bytes.push(NAMED_MOVE_SETS.len() as u8);
+                push_varint(&mut bytes, offsets.len() as u64);
+                for (dx, dy) in offsets {
+                    push_signed(&mut bytes, *dx);
+                    push_signed(&mut bytes, *dy);
+                }
+            }
+            named => bytes.push(
+                NAMED_MOVE_SETS
+                    .iter()
+                    .position(|move_set| move_set == named)
+                    .unwrap_or(0) as u8,
+            ),
+        }
+
+        push_varint(&mut bytes, self.geometry.width as u64);
+        push_varint(&mut bytes, self.geometry.height as u64);
+        push_varint(&mut bytes, self.geometry.reach as u64);
+        push_varint(&mut bytes, self.geometry.spawn_distance as u64);
+        bytes.push(
+            self.distance_hints as u8
+                | (self.zmoves_allowed as u8) << 1
+                | (self.fog_radius.is_some() as u8) << 2
+                | (self.direction_hints as u8) << 3
+                | (self.hint_noise.is_some() as u8) << 4
+                | (!tile_rest.is_empty() as u8) << 5
+                | ((self.palette != Palette::Standard) as u8) << 6,
+        );
+        // only written with the fog, so the codes without it didn't change
+        if let Some(radius) = self.fog_radius {
+            push_varint(&mut bytes, radius as u64);
+        }
+        if let Some(noise) = self.hint_noise {
+            push_varint(&mut bytes, noise.spread as u64);
+            push_varint(&mut bytes, noise.noise_percent as u64);
+            push_varint(&mut bytes, noise.lie_percent as u64);
+        }
+        if !tile_rest.is_empty() {
+            push_varint(&mut bytes, tile_rest.len() as u64);
+            for c in tile_rest {
+                push_varint(&mut bytes, c as u64);
+            }
+        }
+        if self.palette != Palette::Standard {
+            bytes.push(
+                Palette::ALL
+                    .iter()
+                    .position(|palette| *palette == self.palette)
+                    .unwrap_or(0) as u8,
+            );
+        }
+        bytes.push(checksum(&bytes));
+
+        // 5 bits per char, the last char is padded with zeros
+        let mut chars: Vec<char> = Vec::new();
+        let (mut buffer, mut bits) = (0u32, 0u32);
+        for byte in bytes {
+            buffer = buffer << 8 | byte as u32;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                chars.push(Board::GAME_CODE_ALPHABET[(buffer >> bits & 0x1f) as usize] as char);
+            }
+        }
+        if bits > 0 {
+            chars.push(Board::GAME_CODE_ALPHABET[(buffer << (5 - bits) & 0x1f) as usize] as char);
+        }
+
+        chars
+            .chunks(Board::GAME_CODE_GROUP)
+            .map(|group| group.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("-")
+    }
+}
+
+impl FromStr for GameSettings {
+    type Err = BoardError;
+
+    /// reads the settings back from a game code, see `GameSettings::to_code`
+    /// the dashes and the spaces are ignored, the case too,
+    /// and like in Crockford's base 32 `O` is read `0`, `I` and `L` are read `1`
+    ///
+    /// # Arguments
+    /// * `s` - the game code
+    ///
+    /// # Returns
+    /// * `GameSettings` - the settings of the code
+    /// * `BoardError::FailedParse` - if a char isn't in the code alphabet, pointing at it
+    /// * `BoardError::InvalidFormat` - if the code is cut, altered, or from another version
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim();
+        let mut bytes: Vec<u8> = Vec::new();
+        let (mut buffer, mut bits) = (0u32, 0u32);
+        for (column, c) in code.chars().enumerate() {
+            let c = match c.to_ascii_uppercase() {
+                '-' | ' ' => continue,
+                'O' => '0',
+                'I' | 'L' => '1',
+                c => c,
+            };
+            let value = Board::GAME_CODE_ALPHABET
+                .iter()
+                .position(|&letter| letter as char == c)
+                .ok_or_else(|| {
+                    BoardError::FailedParse(
+                        ErrorContext::new("This char can't be in a game code")
+                            .with_input(code)
+                            .at_column(column),
+                    )
+                })?;
+            buffer = buffer << 5 | value as u32;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((buffer >> bits) as u8);
+            }
+        }
+
+        let content = match bytes.split_last() {
+            Some((sum, content)) if checksum(content) == *sum => content,
+            _ => return Err(damaged_code(code)),
+        };
+        let mut reader = CodeReader {
+            bytes: content,
+            code,
+        };
+        if reader.byte()? != Board::GAME_CODE_VERSION {
+            return Err(BoardError::InvalidFormat(
+                ErrorContext::new("This game code comes from another version of the game")
+                    .with_input(code),
+            ));
+        }
+
+        let mut seed_bytes = [0u8; 8];
+        for byte in seed_bytes.iter_mut() {
+            *byte = reader.byte()?;
+        }
+        let seed = u64::from_le_bytes(seed_bytes);
+        let player_color = match reader.byte()? {
+            8 => Color::Ansi256(reader.byte()?),
+            9 => Color::Rgb(reader.byte()?, reader.byte()?, reader.byte()?),
+            tag => *NAMED_COLORS
+                .get(tag as usize)
+                .ok_or_else(|| damaged_code(code))?,
+        };
+        let mut player_tile =
+            String::from(char::from_u32(reader.u32()?).ok_or_else(|| damaged_code(code))?);
+        let move_set = match reader.byte()? as usize {
+            tag if tag == NAMED_MOVE_SETS.len() => {
+                let count = reader.varint()?;
+                let offsets = (0..count)
+                    .map(|_| Ok((reader.signed()?, reader.signed()?)))
+                    .collect::<Result<Vec<(i64, i64)>, BoardError>>()?;
+                // a real code never has an offset longer than a board side
+                match offsets.is_empty() || MoveSet::check_offsets(&offsets).is_err() {
+                    true => return Err(damaged_code(code)),
+                    false => MoveSet::Custom(offsets),
+                }
+            }
+            tag => NAMED_MOVE_SETS
+                .get(tag)
+                .cloned()
+                .ok_or_else(|| damaged_code(code))?,
+        };
+        let geometry = Geometry::new(reader.u32()?, reader.u32()?, reader.u32()?, reader.u32()?)
+            .map_err(|_| damaged_code(code))?;
+        let flags = reader.byte()?;
+        let fog_radius = match flags & 4 != 0 {
+            true => Some(reader.u32()?),
+            false => None,
+        };
+        let hint_noise = match flags & 16 != 0 {
+            true => Some(
+                HintNoise::new(reader.u32()?, reader.u32()?, reader.u32()?)
+                    .map_err(|_| damaged_code(code))?,
+            ),
+            false => None,
+        };
+        if flags & 32 != 0 {
+            for _ in 0..reader.varint()? {
+                player_tile.push(char::from_u32(reader.u32()?).ok_or_else(|| damaged_code(code))?);
+            }
+        }
+        let player_tile = Tile::from_str(&player_tile).map_err(|_| damaged_code(code))?;
+        let palette = match flags & 64 != 0 {
+            true => *Palette::ALL
+                .get(reader.byte()? as usize)
+                .ok_or_else(|| damaged_code(code))?,
+            false => Palette::Standard,
+        };
+        // like in the profiles, a color lost on the water isn't played
+        if palette.check_player_color(&player_color).is_err() {
+            return Err(BoardError::InvalidFormat(
+                ErrorContext::new("This game code has a color that can't be seen on the water")
+                    .with_input(code),
+            ));
+        }
+        if !reader.bytes.is_empty() {
+            return Err(damaged_code(code));
+        }
+
+        Ok(GameSettings {
+            seed,
+            player_color,
+            player_tile,
+            move_set,
+            geometry,
+            distance_hints: flags & 1 != 0,
+            direction_hints: flags & 8 != 0,
+            zmoves_allowed: flags & 2 != 0,
+            fog_radius,
+            hint_noise,
+            palette,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matches::assert_matches;
+
+    #[test]
+    fn codes_give_the_settings_back() {
+        let default = GameSettings::get_default_settings();
+        assert_eq!(GameSettings::from_str(&default.to_code()).unwrap(), default);
+
+        let settings = GameSettings {
+            seed: u64::MAX - 12,
+            player_color: Color::Rgb(20, 200, 255),
+            player_tile: Tile::from_str("🇨🇭").unwrap(),
+            move_set: MoveSet::Custom(vec![(3, -1), (-70, 2)]),
+            geometry: Geometry::new(40, 9, 3, 0).unwrap(),
+            distance_hints: false,
+            direction_hints: true,
+            zmoves_allowed: true,
+            fog_radius: Some(3),
+            hint_noise: Some(HintNoise::new(2, 30, 10).unwrap()),
+            palette: Palette::Tritanopia,
+        };
+        let code = settings.to_code();
+        assert_eq!(GameSettings::from_str(&code).unwrap(), settings);
+        // typed by hand
+        assert_eq!(
+            GameSettings::from_str(&format!(" {} ", code.to_lowercase().replace('-', " ")))
+                .unwrap(),
+            settings
+        );
+
+        let mut settings = GameSettings::get_new_settings();
+        settings.player_color = Color::Ansi256(130);
+        settings.move_set = MoveSet::Bishop;
+        assert_eq!(
+            GameSettings::from_str(&settings.to_code()).unwrap(),
+            settings
+        );
+    }
+
+    #[test]
+    fn codes_are_short_and_readable() {
+        let code = GameSettings::get_new_settings().to_code();
+        assert!(code.len() <= 36, "{} is too long", code);
+        assert!(code
+            .chars()
+            .all(|c| c == '-' || Board::GAME_CODE_ALPHABET.contains(&(c as u8))));
+        assert!(!code.contains(['I', 'L', 'O', 'U']));
+        assert_eq!(
+            GameSettings::from_str(&code.replace('1', "l").replace('0', "o")).unwrap(),
+            GameSettings::from_str(&code).unwrap()
+        );
+    }
+
+    #[test]
+    fn damaged_codes_are_refused() {
+        let code = GameSettings::get_default_settings().to_code();
+
+        let err = GameSettings::from_str("0G0U-1234").unwrap_err();
+        assert_matches!(err, BoardError::FailedParse(_));
+        assert_eq!(err.context().unwrap().column, Some(3));
+
+        // a changed char and a cut code
+        let mut changed: Vec<char> = code.chars().collect();
+        changed[2] = if changed[2] == 'A' { 'B' } else { 'A' };
+        assert_matches!(
+            GameSettings::from_str(&changed.iter().collect::<String>()).unwrap_err(),
+            BoardError::InvalidFormat(_)
+        );
+        assert_matches!(
+            GameSettings::from_str(&code[..code.len() - 3]).unwrap_err(),
+            BoardError::InvalidFormat(_)
+        );
+        assert_matches!(
+            GameSettings::from_str("").unwrap_err(),
+            BoardError::InvalidFormat(_)
+        );
+
+        // a well formed code with a color the profiles refuse
+        let mut settings = GameSettings::get_default_settings();
+        settings.player_color = Color::Blue;
+        assert_eq!(
+            GameSettings::from_str(&settings.to_code())
+                .unwrap_err()
+                .context()
+                .unwrap()
+                .message,
+            "This game code has a color that can't be seen on the water"
+        );
+
+        // a well formed code with an offset no board has
+        let mut settings = GameSettings::get_default_settings();
+        settings.move_set = MoveSet::Custom(vec![(i64::MAX, 0)]);
+        assert_matches!(
+            GameSettings::from_str(&settings.to_code()).unwrap_err(),
+            BoardError::InvalidFormat(_)
+        );
+    }
+}
@@ -1,16 +1,30 @@
-use super::{Board, Color};
+use super::{Board, Color, Geometry, HintNoise, MoveSet, Palette, Tile};
+use rand::{rngs::OsRng, RngCore};
 
 /// the struct that represent Game settings
 /// This is used to handle settings in a quick and compact way
-#[derive(Debug, PartialEq)]
+/// the rule toggles only take away help from the player, they are off in the harder profiles
+/// the fog radius is how far the player sees, the whole board is drawn without it
+/// the hint noise blurs the distance hints of the expert mode, they are exact without it
+/// the palette colors the water and the searched cells, there are some for the color blindnesses
+#[derive(Debug, PartialEq, Clone)]
 pub struct GameSettings {
     pub seed: u64,
     pub player_color: Color,
-    pub player_tile: char,
+    pub player_tile: Tile,
+    pub move_set: MoveSet,
+    pub geometry: Geometry,
+    pub distance_hints: bool,
+    pub direction_hints: bool,
+    pub zmoves_allowed: bool,
+    pub fog_radius: Option<u32>,
+    pub hint_noise: Option<HintNoise>,
+    pub palette: Palette,
 }
 
 impl GameSettings {
     /// this grabs the default settings, it makes life easier
+    /// the seed is the fixed `DEFAULT_SEED`, so the board is always the same one
     ///
     /// # Returns
     /// * `GamesSettings` - the default game settings, in a compact and easy struct
@@ -18,7 +32,26 @@ impl GameSettings {
         GameSettings {
             seed: Board::DEFAULT_SEED,
             player_color: Board::DEFAULT_PLAYER_COLOR,
-            player_tile: Board::DEFAULT_PLAYER_TILE,
+            player_tile: Tile::from(Board::DEFAULT_PLAYER_TILE),
+            move_set: Board::DEFAULT_MOVE_SET,
+            geometry: Geometry::default(),
+            distance_hints: true,
+            direction_hints: false,
+            zmoves_allowed: true,
+            fog_radius: None,
+            hint_noise: None,
+            palette: Palette::Standard,
+        }
+    }
+
+    /// the default settings on a seed drawn from the OS entropy, what a casual game starts with
+    ///
+    /// # Returns
+    /// * `GameSettings` - the default game settings with a random seed
+    pub fn get_new_settings() -> GameSettings {
+        GameSettings {
+            seed: OsRng.next_u64(),
+            ..GameSettings::get_default_settings()
         }
     }
 }
@@ -0,0 +1,119 @@
+use super::Board;
+
+/// The shape of the torus and how far a move can go on it
+///
+/// the defaults are the original 15x15 board with a reach of 4
+/// the zmove speeds stay capped by `MOVE_MAX_DISTANCE`, whatever the reach
+///
+/// # Attributes
+/// * `width` - the number of columns of the board
+/// * `height` - the number of rows of the board
+/// * `reach` - the biggest x or y distance of a move
+/// * `spawn_distance` - the smallest distance between the player and the treasure at the start
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Geometry {
+    pub width: u32,
+    pub height: u32,
+    pub reach: u32,
+    pub spawn_distance: u32,
+}
+
+impl Default for Geometry {
+    fn default() -> Self {
+        Geometry {
+            width: Board::DEFAULT_BOARD_WIDTH,
+            height: Board::DEFAULT_BOARD_HEIGHT,
+            reach: Board::MOVE_MAX_DISTANCE,
+            spawn_distance: Board::DEFAULT_SPAWN_DISTANCE,
+        }
+    }
+}
+
+impl Geometry {
+    /// builds a geometry, refusing the ones the game can't be played on
+    /// a side has to fit the board printing, and the reach has to leave cells out of reach
+    /// the spawn distance has to leave a cell that far from any player, see `max_spawn_distance`
+    ///
+    /// # Arguments
+    /// * `width` - the number of columns
+    /// * `height` - the number of rows
+    /// * `reach` - the biggest x or y distance of a move
+    /// * `spawn_distance` - the smallest distance between the player and the treasure at the start
+    ///
+    /// # Returns
+    /// * `Geometry` - the checked geometry
+    /// * `Err(String)` - telling which value is wrong
+    pub fn new(
+        width: u32,
+        height: u32,
+        reach: u32,
+        spawn_distance: u32,
+    ) -> Result<Geometry, String> {
+        let sides = Board::MIN_BOARD_SIDE..=Board::MAX_BOARD_SIDE;
+        if !sides.contains(&width) || !sides.contains(&height) {
+            return Err(format!(
+                "the board size {}x{} isn't between {2}x{2} and {3}x{3}",
+                width,
+                height,
+                Board::MIN_BOARD_SIDE,
+                Board::MAX_BOARD_SIDE
+            ));
+        }
+        if reach == 0 || reach >= width.min(height) {
+            return Err(format!(
+                "the reach {} has to be between 1 and {}",
+                reach,
+                width.min(height) - 1
+            ));
+        }
+        let max_spawn_distance = Geometry::max_spawn_distance(width, height);
+        if spawn_distance > max_spawn_distance {
+            return Err(format!(
+                "the spawn distance {} has to be at most {}",
+                spawn_distance, max_spawn_distance
+            ));
+        }
+        Ok(Geometry {
+            width,
+            height,
+            reach,
+            spawn_distance,
+        })
+    }
+
+    /// the farthest two cells can be on a torus, half of the longest side
+    /// every cell has a cell that far, so the treasure can always be placed
+    ///
+    /// # Arguments
+    /// * `width` - the number of columns
+    /// * `height` - the number of rows
+    ///
+    /// # Returns
+    /// * `u32` - the biggest spawn distance of this board size
+    pub fn max_spawn_distance(width: u32, height: u32) -> u32 {
+        width.max(height) / 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geometry_limits() {
+        assert_eq!(Geometry::new(15, 15, 4, 2).unwrap(), Geometry::default());
+        assert!(Geometry::new(30, 8, 7, 15).is_ok());
+        assert!(Geometry::new(1, 15, 4, 2).is_err());
+        assert!(Geometry::new(15, Board::MAX_BOARD_SIDE + 1, 4, 2).is_err());
+        assert!(Geometry::new(15, 15, 0, 2).is_err());
+        assert_eq!(
+            Geometry::new(20, 5, 5, 2).unwrap_err(),
+            "the reach 5 has to be between 1 and 4"
+        );
+        assert!(Geometry::new(2, 2, 1, 0).is_ok());
+        assert_eq!(
+            Geometry::new(30, 8, 7, 16).unwrap_err(),
+            "the spawn distance 16 has to be at most 15"
+        );
+    }
+}
@@ -0,0 +1,17 @@
+use super::Position;
+
+/// Something that happened during a game
+/// The list of those is enough to replay a whole game
+///
+/// # Variants
+/// * `Start` - where the player started
+/// * `Move` - where the player landed after a teleport or a zmove, already modulated
+/// * `Search` - where the player searched
+/// * `Scan` - the cells an item told the treasure isn't on, they are marked like searched ones
+#[derive(Debug, PartialEq, Clone)]
+pub enum GameEvent {
+    Start(Position),
+    Move(Position),
+    Search(Position),
+    Scan(Vec<Position>),
+}
@@ -1,6 +1,21 @@
-use super::{BoardError, Color, Command, Position, Zmove};
+use super::{
+    Bindings, Board, BoardError, Color, Command, FromStr, GameSettings, Geometry, HintNoise,
+    MoveSet, Palette, Position, Target, Tile, Zmove,
+};
 use read_input::prelude::*;
 
+/// the one formatter used by every prompt to show an error
+/// the numeric code comes first, then the message, the expected range and the caret if known
+///
+/// # Arguments
+/// * `err` - the error to show
+///
+/// # Returns
+/// * `String` - the text shown to the player
+pub fn format_error(err: &BoardError) -> String {
+    format!("E{:02}: {}", err.numeric_code(), err)
+}
+
 /// a method to get a user submitted seed value
 /// this method only stop when a correct seed is given
 ///
@@ -17,24 +32,43 @@ pub fn get_seed_setting() -> u64 {
 /// this method only stops when a correct color is given
 /// it uses the from str method of the Color struct
 /// that from str method wasn't done by me, it comes with the termcolor crate
+/// a color that can't be seen on the water is refused, see `Palette::check_player_color`
+///
+/// # Arguments
+/// * `palette` - the palette of the game, the color is checked against its water
 ///
 /// # Returns
 /// * `Color` - the color given by the player
-pub fn get_color_setting() -> Color {
+pub fn get_color_setting(palette: Palette) -> Color {
     input()
-        .msg("Please input your color.\nWARNING, the closer to blue the harder the game!\n[e.g. 'red', 'cyan', '2426' ,'23,144,643']: ")
+        .msg("Please input your color.\nWARNING, the closer to the water color the harder the game!\n[e.g. 'red', 'cyan', '2426' ,'23,144,643']: ")
         .err("That is not a legal color, try again [e.g. 'red', 'cyan', '2426' ,'23,144,643']: ")
+        .add_err_test(
+            move |color| palette.check_player_color(color).is_ok(),
+            "That color can't be seen on the water, try another one [e.g. 'yellow', 'green']: ",
+        )
+        .get()
+}
+
+/// a method to get a user submitted palette, the colors of the water and of the searched cells
+///
+/// # Returns
+/// * `Palette` - the palette given by the player
+pub fn get_palette_setting() -> Palette {
+    input()
+        .msg("Please input your palette.\n[e.g. 'standard', 'deuteranopia', 'protanopia', 'tritanopia']: ")
+        .err("That is not a palette, try again [e.g. 'standard', 'deuteranopia', 'protanopia', 'tritanopia']: ")
         .get()
 }
 
-/// a method to get a user submitted tile in `char` format
+/// a method to get a user submitted tile, a single character or emoji
 ///
 /// # Returns
-/// * `char` - the tile given by the player
-pub fn get_tile_setting() -> char {
+/// * `Tile` - the tile given by the player
+pub fn get_tile_setting() -> Tile {
     input()
-        .msg("Please input the tile that will represent you.\nWARNING, the closer to the '~' char, the harder the game!\n[e.g. 'r', '#', '☺' ,'A']: ")
-        .err("That is not a char, try again [e.g. 'r', '#', '☺' ,'A']: ")
+        .msg("Please input the tile that will represent you.\nWARNING, the closer to the '~' char, the harder the game!\n[e.g. 'r', '#', '☺' ,'A', '🦀']: ")
+        .err("That is not a single character, try again [e.g. 'r', '#', '☺' ,'A', '🦀']: ")
         .get()
 }
 
@@ -51,39 +85,39 @@ pub fn get_choice_setting() -> String {
     input().msg("Please input your choice: ").get()
 }
 
-/// a method to get a user submitted Position
-/// this uses the from str method from position
+/// a method to get a user submitted teleport target
+/// this uses the from str method from target
 ///
-/// A position can be outside of the board, it will be modulated to fit on it
-/// this panics if an unexpected error arise
+/// A target can be outside of the board, it will be modulated to fit on it
 ///
 /// # Returns
-/// * `Position` - the position where the user wants to be teleported
-pub fn get_position_for_teleport() -> Position {
+/// * `Target` - where the user wants to be teleported, resolved by the board
+pub fn get_target_for_teleport() -> Target {
     input()
         .msg("You can enter the position where you want to go \
-        [e.g. '12,13' '[12,0xc]' '(0x12,14)'] \
+        [e.g. '12,13' '[12,0xc]' '(0x12,14)' '0b101,0o17'] \
+        \n Signed or '~' values are relative to you [e.g. '+3,-2' '~-1,~4' '~,3'] \
+        \n You can also enter 'here', 'center' or 'origin'.\
         \n You can go to positions outside of the board, as the board is a torus, they will be corrected.\
         \nEnter your choice: ")
-        .err_match(|e| {
-            Some(match e {
-                BoardError::InvalidFormat(s) => format!("{}", s),
-                BoardError::Not2Dimensional(u) => format!("Your value had {} dimension(s) instead of 2",u),
-                BoardError::FailedParse(s) => format!("{}", s),
-                _=> panic!("impossible error"),
-            })
-
-        }).get()
+        .err_match(|e| Some(format_error(e))).get()
 }
 
 /// a method to get a user submitted zmove
 /// this uses the from str method from zmove
 ///
 /// A zmove can go outside the board, a warparound will be applied
+/// only the zmoves fitting the move set are accepted
+///
+/// # Arguments
+/// * `move_set` - the move set of the game
+/// * `geometry` - the board, its reach bounds the move set
 ///
 /// # Returns
 /// * `Zmove` - the zmove where the user wants to do
-pub fn get_zmove() -> Zmove {
+pub fn get_zmove(move_set: &MoveSet, geometry: &Geometry) -> Zmove {
+    let move_set = move_set.clone(); // the test has to own it
+    let geometry = *geometry;
     input()
         .msg("You can enter the zmove you want: direction then speed \
         [e.g. '2,4' '[0x1,1]' '(0x9,2)'] \
@@ -93,40 +127,159 @@ pub fn get_zmove() -> Zmove {
         \n1\t2\t3
         \n Zmoves are limted, for exemple you can't go 2 up and one left, but they are easier to use than move\
         \nEnter your choice: ")
-        .err_match(|e| {
-            Some(match e {
-                BoardError::InvalidFormat(s) => format!("{}", s),
-                BoardError::Not2Dimensional(u) => format!("Your value had {} dimension(s) instead of 2",u),
-                BoardError::FailedParse(s) => format!("{}", s),
-                BoardError::InvalidMove(s) => format!("{}", s),
-                _=> panic!("impossible error"),
-            })
+        .add_err_test(
+            move |zmove| move_set.allows_zmove(zmove, &geometry),
+            "This zmove doesn't fit your move set, try again: ",
+        )
+        .err_match(|e| Some(format_error(e))).get()
+}
 
-        }).get()
+/// a method to get a user submitted move set
+/// the named ones are parsed with the from str method from move set
+///
+/// # Returns
+/// * `Some(MoveSet)` - the named move set chosen by the player
+/// * `None` - if the player wants a custom move set, see `get_move_set_path`
+pub fn get_move_set_setting() -> Option<MoveSet> {
+    let choice: String = input()
+        .msg("Please input your move set.\n[e.g. 'classic', 'king', 'knight', 'rook', 'bishop' or 'custom' to load a file]: ")
+        .add_err_test(
+            |choice: &String| choice.trim() == "custom" || MoveSet::from_str(choice).is_ok(),
+            "That is not a move set, try again [e.g. 'classic', 'knight', 'custom']: ",
+        )
+        .get();
+    MoveSet::from_str(&choice).ok()
 }
 
-/// a method to get a user submitted Command
-/// this uses the from str method from command
-/// this panics if an unexpected error arise
+/// a method to get the path of a custom move set file
+/// the file has one 'dx,dy' offset per line
 ///
 /// # Returns
-/// * `Command` - the command the will decide the next step
-pub fn get_choice_command() -> Command {
+/// * `String` - the path given by the player
+pub fn get_move_set_path() -> String {
     input()
-        .msg("Please enter your action [e.g. m, 2, search]: ")
-        .err_match(|e| {
-            Some(match e {
-                BoardError::InvalidCommand(s) => format!("{}", s),
-                BoardError::FailedParse(s) => format!("{}", s),
-                BoardError::TooManyArguments(u) => format!("{} is too many elements", u),
-                BoardError::InvalidMove(s) => format!("{}", s),
-                BoardError::InvalidFormat(s) => format!("{}", s),
-                _ => panic!("impossible error"),
-            })
-        })
+        .msg("Please enter the path of your move set file, one 'dx,dy' offset per line [e.g. 'camel.txt']: ")
         .get()
 }
 
+/// a method to get a user submitted board size, written like a position
+/// the size is checked with the reach by the board
+///
+/// # Returns
+/// * `(u32, u32)` - the width and the height given by the player
+pub fn get_board_size_setting() -> (u32, u32) {
+    let size: Position = input()
+        .msg(format!(
+            "Please enter the width and the height of the board, from {0},{0} to {1},{1} [e.g. '15,15' '30,20']: ",
+            Board::MIN_BOARD_SIDE,
+            Board::MAX_BOARD_SIDE
+        ))
+        .err_match(|e| Some(format_error(e)))
+        .get();
+    (size.x, size.y)
+}
+
+/// a method to get a user submitted reach, the biggest x or y distance of a move
+///
+/// # Returns
+/// * `u32` - the reach given by the player
+pub fn get_reach_setting() -> u32 {
+    input()
+        .msg("Please enter how far a move can go [e.g. '4']: ")
+        .err("That's not a positive integer, [e.g. '4']: ")
+        .get()
+}
+
+/// a method to get a user submitted spawn distance,
+/// the smallest distance between the player and the treasure at the start
+///
+/// # Returns
+/// * `u32` - the spawn distance given by the player
+pub fn get_spawn_distance_setting() -> u32 {
+    input()
+        .msg("Please enter how far from you the treasure has to be at the start, 0 for anywhere [e.g. '5']: ")
+        .err("That's not a positive integer, [e.g. '5']: ")
+        .get()
+}
+
+/// a method to get a user submitted fog radius, how far the player sees
+///
+/// # Returns
+/// * `Some(u32)` - the radius given by the player
+/// * `None` - if the player wants to see the whole board
+pub fn get_fog_radius_setting() -> Option<u32> {
+    let choice: String = input()
+        .msg("Please enter how far you can see, or 'off' to see the whole board [e.g. '3' 'off']: ")
+        .add_err_test(
+            |choice: &String| choice.trim() == "off" || choice.trim().parse::<u32>().is_ok(),
+            "That's not a positive integer or 'off', [e.g. '3']: ",
+        )
+        .get();
+    choice.trim().parse().ok()
+}
+
+/// a method to get a user submitted hint noise, how the distance hints are blurred
+///
+/// # Returns
+/// * `Some(HintNoise)` - the noise given by the player
+/// * `None` - if the player wants the exact hints
+pub fn get_hint_noise_setting() -> Option<HintNoise> {
+    let choice: String = input()
+        .msg("Please enter how far off a hint can be, how often in %, and how often it lies in %, or 'off' for the exact hints [e.g. '1,30,10' 'off']: ")
+        .add_err_test(
+            |choice: &String| choice.trim() == "off" || HintNoise::from_str(choice).is_ok(),
+            "That's not a spread and two odds adding up to at most 100, or 'off', [e.g. '1,30,10']: ",
+        )
+        .get();
+    HintNoise::from_str(&choice).ok()
+}
+
+/// a method to get the name of a profile of the configuration file
+///
+/// # Returns
+/// * `String` - the name given by the player, trimmed
+pub fn get_profile_name() -> String {
+    input::<String>()
+        .msg("Please enter the name of the profile [e.g. 'easy' 'team-race']: ")
+        .add_test(|name| !name.trim().is_empty())
+        .err("A profile needs a name, try again: ")
+        .get()
+        .trim()
+        .to_string()
+}
+
+/// a method to get the settings of a shared game code
+/// this uses the from str method from game settings
+///
+/// # Returns
+/// * `GameSettings` - every setting of the shared game
+pub fn get_game_code() -> GameSettings {
+    input()
+        .msg("Please paste the game code [e.g. '0810-0000-...']: ")
+        .err_match(|e| Some(format_error(e)))
+        .get()
+}
+
+/// a method to get a user submitted Command
+/// the custom bindings are tried first, then the from str method from command
+///
+/// # Arguments
+/// * `bindings` - the custom key bindings
+///
+/// # Returns
+/// * `Command` - the command the will decide the next step
+pub fn get_choice_command(bindings: &Bindings) -> Command {
+    loop {
+        let choice: String = input()
+            .msg("Please enter your action [e.g. m, 2, search]: ")
+            .get();
+        match bindings.parse_command(&choice) {
+            Ok(command) => return command,
+            Err(err) => println!("{}", format_error(&err)),
+        }
+    }
+}
+
 /// a method to get a user submitted yes or no
 /// no uppercase allowed, yes it's stricter than the other for no reasons.
 ///
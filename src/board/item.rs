@@ -0,0 +1,126 @@
+use super::{Board, BoardError, Direction, ErrorContext};
+use serde::Serialize;
+use std::fmt;
+
+/// The items the player can use instead of a search, each one has a few uses
+///
+/// # Variants
+/// * `Sonar` - tells if the treasure is within `SONAR_RADIUS` of the player
+/// * `Compass` - tells the rough direction of the treasure
+/// * `MetalDetector` - searches the 3x3 area around the player at once
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Item {
+    Sonar,
+    Compass,
+    MetalDetector,
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Item::Sonar => write!(f, "sonar"),
+            Item::Compass => write!(f, "compass"),
+            Item::MetalDetector => write!(f, "metal detector"),
+        }
+    }
+}
+
+/// What an item told the player
+///
+/// # Variants
+/// * `Sonar` - true if the treasure is within the sonar radius
+/// * `Compass` - the rough direction of the treasure, none if the player is on it
+/// * `MetalDetector` - true if the treasure was in the searched area, the game is won then
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemResult {
+    Sonar(bool),
+    Compass(Option<Direction>),
+    MetalDetector(bool),
+}
+
+/// The uses left of each item, the game starts with `SONAR_USES`, `COMPASS_USES`
+/// and `METAL_DETECTOR_USES`
+///
+/// # Attributes
+/// * `sonars` - the sonar uses left
+/// * `compasses` - the compass uses left
+/// * `metal_detectors` - the metal detector uses left
+#[derive(Debug, PartialEq, Clone)]
+pub struct Inventory {
+    sonars: u32,
+    compasses: u32,
+    metal_detectors: u32,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Inventory {
+            sonars: Board::SONAR_USES,
+            compasses: Board::COMPASS_USES,
+            metal_detectors: Board::METAL_DETECTOR_USES,
+        }
+    }
+}
+
+impl Inventory {
+    /// the uses left of an item
+    pub fn count(&self, item: Item) -> u32 {
+        match item {
+            Item::Sonar => self.sonars,
+            Item::Compass => self.compasses,
+            Item::MetalDetector => self.metal_detectors,
+        }
+    }
+
+    /// uses an item once
+    ///
+    /// # Arguments
+    /// * `item` - the item to use
+    ///
+    /// # Returns
+    /// * `Ok(_)` - if the item had a use left, it's one less now
+    /// * `Err(BoardError::InvalidCommand)` - if there is none left, nothing changed
+    pub fn take(&mut self, item: Item) -> Result<(), BoardError> {
+        let count = match item {
+            Item::Sonar => &mut self.sonars,
+            Item::Compass => &mut self.compasses,
+            Item::MetalDetector => &mut self.metal_detectors,
+        };
+        match *count {
+            0 => Err(BoardError::InvalidCommand(ErrorContext::new(&format!(
+                "You have no {} left",
+                item
+            )))),
+            _ => {
+                *count -= 1;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matches::assert_matches;
+
+    #[test]
+    fn items_run_out() {
+        let mut inventory = Inventory::default();
+        assert_eq!(inventory.count(Item::Sonar), Board::SONAR_USES);
+
+        for _ in 0..Board::COMPASS_USES {
+            assert!(inventory.take(Item::Compass).is_ok());
+        }
+        assert_eq!(inventory.count(Item::Compass), 0);
+        assert_matches!(
+            inventory.take(Item::Compass).unwrap_err(),
+            BoardError::InvalidCommand(_)
+        );
+        assert_eq!(inventory.count(Item::Compass), 0);
+        // the other items are untouched
+        assert_eq!(inventory.count(Item::Sonar), Board::SONAR_USES);
+    }
+}
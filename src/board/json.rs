@@ -0,0 +1,542 @@
+use super::{
+    Board, BoardError, Command, Direction, ErrorContext, FromStr, GameSettings, Item, ItemResult,
+    Position, Target, Zmove,
+};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde::Serialize;
+use std::io::{self, BufRead, Write};
+
+/// The state of a game, as seen by the json output
+#[derive(Debug, PartialEq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameStatus {
+    #[default]
+    Playing,
+    Won,
+    Quit,
+}
+
+/// The json object emitted after each turn
+///
+/// # Attributes
+/// * `turn` - the number of moves, searches and item uses, 0 is the starting state
+/// * `player` - the player position
+/// * `tracker` - the bitmap of the cells the treasure is known not to be on, searched or cleared by an item
+/// * `last_command` - the name of the command executed this turn, none at the start
+/// * `hint` - the distance to the treasure if this turn was an unsuccessful search
+/// * `direction` - the direction of the treasure after an unsuccessful search, with the direction hints
+/// * `item` - what the item used this turn told, like `{"sonar": true}`
+/// * `status` - the state of the game after this turn
+#[derive(Debug, Serialize)]
+pub struct TurnReport {
+    pub turn: u32,
+    pub player: Position,
+    pub tracker: TrackerBitmap,
+    pub last_command: Option<&'static str>,
+    pub hint: Option<u32>,
+    pub direction: Option<Direction>,
+    pub item: Option<ItemResult>,
+    pub status: GameStatus,
+}
+
+/// The tracker of a turn report, one bit per cell so even the biggest boards stay compact
+///
+/// # Attributes
+/// * `width` `height` - the size of the board
+/// * `bits` - the cells in base64, packed like `Tracker::packed_bits`,
+///   cell (x, y) is the bit `i % 8` of the byte `i / 8`, with `i = y * width + x`
+#[derive(Debug, Serialize)]
+pub struct TrackerBitmap {
+    pub width: u32,
+    pub height: u32,
+    pub bits: String,
+}
+
+/// The json object emitted when a command was rejected
+///
+/// # Attributes
+/// * `error` - the stable code of the error, see `BoardError::code`
+/// * `code` - the stable numeric code of the error, see `BoardError::numeric_code`
+/// * `message` - the human readable message
+/// * `input` - the offending input, if known
+/// * `column` - the char index of the bad token in `input`, if known
+/// * `expected` - the inclusive range the value should have been in, if known
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    pub error: &'static str,
+    pub code: u16,
+    pub message: String,
+    pub input: Option<String>,
+    pub column: Option<usize>,
+    pub expected: Option<(i64, i64)>,
+}
+
+impl From<BoardError> for ErrorReport {
+    fn from(err: BoardError) -> Self {
+        match err.context() {
+            Some(context) => ErrorReport {
+                error: err.code(),
+                code: err.numeric_code(),
+                message: context.message.clone(),
+                input: context.input.clone(),
+                column: context.column,
+                expected: context.expected,
+            },
+            None => ErrorReport {
+                error: err.code(),
+                code: err.numeric_code(),
+                message: err.to_string(),
+                input: None,
+                column: None,
+                expected: None,
+            },
+        }
+    }
+}
+
+/// gives the stable name of a command for the json output
+///
+/// # Arguments
+/// * `command` - the command to name
+///
+/// # Returns
+/// * `&str` - the name, the zmove shortcut is a `zmove` like the prompted one
+pub fn command_name(command: &Command) -> &'static str {
+    match command {
+        Command::AskTeleport => "move",
+        Command::AskZmove | Command::Zmove(_) => "zmove",
+        Command::Search => "search",
+        Command::Quit => "quit",
+        Command::Export => "export",
+        Command::Sequence { .. } => "sequence",
+        Command::ToggleReach => "reach",
+        Command::ToggleCandidates => "candidates",
+        Command::CycleHeatmap => "heatmap",
+        Command::ListSearches => "searches",
+        Command::Pan(_) => "pan",
+        Command::UseItem(Item::Sonar) => "sonar",
+        Command::UseItem(Item::Compass) => "compass",
+        Command::UseItem(Item::MetalDetector) => "metal_detector",
+    }
+}
+
+/// what an executed command gives to its turn report, see `TurnReport`
+/// the default one is a turn that told nothing, the game goes on
+#[derive(Debug, Default)]
+struct TurnOutcome {
+    hint: Option<u32>,
+    direction: Option<Direction>,
+    item: Option<ItemResult>,
+    status: GameStatus,
+}
+
+/// writes a value as one json line
+fn emit<T: Serialize, W: Write>(output: &mut W, value: &T) -> io::Result<()> {
+    writeln!(output, "{}", serde_json::to_string(value)?)?;
+    output.flush()
+}
+
+/// reads one line, an empty string is returned at the end of the input
+fn next_line<R: BufRead>(input: &mut R) -> io::Result<String> {
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    Ok(line.trim_end().to_string())
+}
+
+impl Board {
+    /// the starting point of the json mode, the game is played with the default settings
+    /// on stdin and stdout, no human text is printed
+    ///
+    /// # Returns
+    /// * `Ok` - if the game closed normally
+    /// * `Err` - if reading or writing failed
+    pub fn play_json_game() -> io::Result<()> {
+        let mut this_board = Board::new(GameSettings::get_default_settings());
+        this_board.run_json_game(&mut io::stdin().lock(), &mut io::stdout().lock())
+    }
+
+    /// plays a whole game where each line of input is a command
+    /// `m` and `z` read their position or zmove from the next line
+    ///
+    /// a turn report is emitted at the start and after each executed command,
+    /// an error report is emitted for each rejected one
+    /// the game stops when it's won, quit, or when the input ends
+    ///
+    /// # Arguments
+    /// * `input` - where the commands are read
+    /// * `output` - where the json lines are written
+    ///
+    /// # Returns
+    /// * `Ok` - if the game closed normally
+    /// * `Err` - if reading or writing failed
+    fn run_json_game<R: BufRead, W: Write>(
+        &mut self,
+        input: &mut R,
+        output: &mut W,
+    ) -> io::Result<()> {
+        emit(
+            output,
+            &self.turn_report(self.turn, None, TurnOutcome::default()),
+        )?;
+
+        loop {
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(()); // the input ended, so does the game
+            }
+
+            let command = match Command::from_str(&line)
+                .and_then(|command| self.check_rules(&command).map(|_| command))
+            {
+                Ok(command) => command,
+                Err(err) => {
+                    emit(output, &ErrorReport::from(err))?;
+                    continue;
+                }
+            };
+            let name = command_name(&command);
+
+            // the searches of this command are stamped with its turn
+            let events = self.history.len();
+            self.turn += 1;
+            let result = self.json_command(command, input)?;
+            self.count_turn(events);
+            match result {
+                Ok(outcome) => {
+                    let is_game_over = outcome.status != GameStatus::Playing;
+                    emit(output, &self.turn_report(self.turn, Some(name), outcome))?;
+                    if is_game_over {
+                        return Ok(());
+                    }
+                }
+                Err(report) => emit(output, &report)?,
+            }
+        }
+    }
+
+    /// executes one command without printing anything
+    ///
+    /// # Arguments
+    /// * `command` - the command to execute
+    /// * `input` - where a position or a zmove is read if the command needs one
+    ///
+    /// # Returns
+    /// * `Ok(Ok(TurnOutcome))` - the command was executed, with what it told
+    /// * `Ok(Err(ErrorReport))` - the command was rejected, nothing changed
+    /// * `Err` - if reading failed
+    fn json_command<R: BufRead>(
+        &mut self,
+        command: Command,
+        input: &mut R,
+    ) -> io::Result<Result<TurnOutcome, ErrorReport>> {
+        Ok(match command {
+            // out of board positions are corrected without asking, there's nobody to ask
+            Command::AskTeleport => Target::from_str(&next_line(input)?)
+                .map(|target| {
+                    Board::coordinate_modulo(
+                        target.resolve(&self.player_coordinates, &self.geometry),
+                        &self.geometry,
+                    )
+                })
+                .and_then(|target| self.teleport_logic(&target))
+                .map(|_| TurnOutcome::default())
+                .map_err(ErrorReport::from),
+            Command::AskZmove => Zmove::from_str(&next_line(input)?)
+                .and_then(|zmove| self.try_zmove(&zmove))
+                .map(|_| TurnOutcome::default())
+                .map_err(ErrorReport::from),
+            Command::Zmove(zmove) => self
+                .try_zmove(&zmove)
+                .map(|_| TurnOutcome::default())
+                .map_err(ErrorReport::from),
+            Command::Sequence { zmoves, search } => match self.check_sequence(&zmoves) {
+                Ok(_) => {
+                    for zmove in zmoves {
+                        // the chain was checked, no zmove can fail
+                        let _ = self.try_zmove(&zmove);
+                    }
+                    Ok(match search {
+                        true => self.search_report(),
+                        false => TurnOutcome::default(),
+                    })
+                }
+                Err(err) => Err(ErrorReport::from(err)),
+            },
+            Command::Search => Ok(self.search_report()),
+            // nothing is drawn here, only the state changes
+            Command::ToggleReach => {
+                self.show_reach = !self.show_reach;
+                Ok(TurnOutcome::default())
+            }
+            Command::ToggleCandidates => {
+                self.show_candidates = !self.show_candidates;
+                Ok(TurnOutcome::default())
+            }
+            Command::CycleHeatmap => {
+                self.heatmap = self.heatmap.next();
+                Ok(TurnOutcome::default())
+            }
+            // the turn reports already tell every search
+            Command::ListSearches => Ok(TurnOutcome::default()),
+            Command::Pan(direction) => {
+                self.pan_view(direction);
+                Ok(TurnOutcome::default())
+            }
+            Command::Quit => Ok(TurnOutcome {
+                status: GameStatus::Quit,
+                ..TurnOutcome::default()
+            }),
+            Command::UseItem(item) => self
+                .use_item_logic(item)
+                .map(|result| TurnOutcome {
+                    item: Some(result),
+                    status: match self.is_treasure_found {
+                        true => GameStatus::Won,
+                        false => GameStatus::Playing,
+                    },
+                    ..TurnOutcome::default()
+                })
+                .map_err(ErrorReport::from),
+            // the json mode only writes on its output, the images are for the players
+            Command::Export => Err(ErrorReport::from(BoardError::InvalidCommand(
+                ErrorContext::new("The export isn't available in json mode").with_input("export"),
+            ))),
+        })
+    }
+
+    /// searches and tells the hints and the status of the game
+    fn search_report(&mut self) -> TurnOutcome {
+        match self.search_logic() {
+            None => TurnOutcome {
+                status: GameStatus::Won,
+                ..TurnOutcome::default()
+            },
+            // each hint is only told if it's on
+            Some(dist) => TurnOutcome {
+                hint: Some(dist).filter(|_| self.distance_hints),
+                direction: self.treasure_direction().filter(|_| self.direction_hints),
+                ..TurnOutcome::default()
+            },
+        }
+    }
+
+    /// the tracker as a bitmap, see `TrackerBitmap`
+    fn tracker_bitmap(&self) -> TrackerBitmap {
+        TrackerBitmap {
+            width: self.geometry.width,
+            height: self.geometry.height,
+            bits: BASE64_STANDARD.encode(self.tracker.packed_bits(&self.geometry)),
+        }
+    }
+
+    fn turn_report(
+        &self,
+        turn: u32,
+        last_command: Option<&'static str>,
+        outcome: TurnOutcome,
+    ) -> TurnReport {
+        TurnReport {
+            turn,
+            player: self.player_coordinates.clone(),
+            tracker: self.tracker_bitmap(),
+            last_command,
+            hint: outcome.hint,
+            direction: outcome.direction,
+            item: outcome.item,
+            status: outcome.status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::MoveSet;
+    use super::*;
+    use serde_json::Value;
+
+    /// tells if a cell is set in the tracker bitmap of a report
+    fn is_known(report: &Value, x: u64, y: u64) -> bool {
+        let tracker = &report["tracker"];
+        let bits = BASE64_STANDARD
+            .decode(tracker["bits"].as_str().unwrap())
+            .unwrap();
+        let index = (y * tracker["width"].as_u64().unwrap() + x) as usize;
+        bits[index / 8] >> (index % 8) & 1 == 1
+    }
+
+    /// plays the given input and gives back every emitted json line
+    fn play(board: &mut Board, input: &str) -> Vec<Value> {
+        let mut output: Vec<u8> = Vec::new();
+        board
+            .run_json_game(&mut input.as_bytes(), &mut output)
+            .unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn json_turns_and_errors() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.player_coordinates = Position { x: 1, y: 2 };
+        test_board.treasure_coordinates = Position { x: 14, y: 14 };
+        let reports = play(&mut test_board, "m\n3,4\ns\nhello\nm\n(3,4]\nq\n");
+
+        assert_eq!(reports.len(), 6);
+        assert_eq!(reports[0]["turn"], 0);
+        assert_eq!(reports[0]["last_command"], Value::Null);
+        assert_eq!(reports[0]["status"], "playing");
+
+        assert_eq!(reports[1]["last_command"], "move");
+        assert_eq!(reports[1]["player"]["x"], 3);
+        assert_eq!(reports[1]["player"]["y"], 4);
+
+        assert_eq!(reports[2]["last_command"], "search");
+        assert_eq!(reports[2]["hint"], 5);
+        // the direction hints are off by default
+        assert_eq!(reports[2]["direction"], Value::Null);
+        assert_eq!(reports[2]["tracker"]["width"], 15);
+        assert_eq!(reports[2]["tracker"]["height"], 15);
+        // 225 bits in 29 bytes
+        assert_eq!(reports[2]["tracker"]["bits"].as_str().unwrap().len(), 40);
+        assert!(!is_known(&reports[1], 3, 4));
+        assert!(is_known(&reports[2], 3, 4));
+        assert!(!is_known(&reports[2], 4, 3));
+
+        assert_eq!(reports[3]["error"], "invalid_command");
+        assert_eq!(reports[4]["error"], "invalid_format");
+        assert_eq!(reports[4]["code"], 3);
+        assert_eq!(reports[4]["input"], "(3,4]");
+
+        // the quit changed nothing, it's not a turn
+        assert_eq!(reports[5]["turn"], 2);
+        assert_eq!(reports[5]["status"], "quit");
+    }
+
+    #[test]
+    fn json_relative_move_wraps() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.player_coordinates = Position { x: 1, y: 13 };
+        let reports = play(&mut test_board, "m\n~-3,+4\nm\ncenter\n");
+
+        assert_eq!(reports[1]["player"]["x"], 13);
+        assert_eq!(reports[1]["player"]["y"], 2);
+        assert_eq!(reports[2]["error"], "invalid_move"); // (7,7) is too far
+    }
+
+    #[test]
+    fn json_move_set_refuses_moves() {
+        let mut settings = GameSettings::get_default_settings();
+        settings.move_set = MoveSet::Knight;
+        let mut test_board = Board::new(settings);
+        test_board.player_coordinates = Position { x: 0, y: 0 };
+        let reports = play(&mut test_board, "8,1\nm\n1,2\n8,1 s\n");
+
+        assert_eq!(reports[1]["error"], "invalid_move");
+        assert_eq!(reports[2]["player"]["x"], 1);
+        assert_eq!(reports[2]["player"]["y"], 2);
+        assert_eq!(reports[3]["error"], "invalid_move");
+        assert_eq!(reports.len(), 4);
+    }
+
+    #[test]
+    fn json_win_ends_game() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.treasure_coordinates = test_board.player_coordinates.clone();
+        let reports = play(&mut test_board, "s\ns\n");
+
+        // the second search is never read
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[1]["status"], "won");
+        assert_eq!(reports[1]["hint"], Value::Null);
+    }
+
+    #[test]
+    fn json_direction_hints() {
+        let mut settings = GameSettings::get_default_settings();
+        settings.direction_hints = true;
+        settings.distance_hints = false;
+        let mut test_board = Board::new(settings);
+        test_board.player_coordinates = Position { x: 1, y: 2 };
+        test_board.treasure_coordinates = Position { x: 14, y: 14 };
+        let reports = play(&mut test_board, "s\n");
+
+        assert_eq!(reports[1]["hint"], Value::Null);
+        assert_eq!(reports[1]["direction"], "down_left");
+    }
+
+    #[test]
+    fn json_items() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.player_coordinates = Position { x: 0, y: 0 };
+        test_board.treasure_coordinates = Position { x: 0, y: 6 };
+        let reports = play(&mut test_board, "sonar\ncompass\nd\nd\n");
+
+        assert_eq!(reports[1]["last_command"], "sonar");
+        assert_eq!(reports[1]["item"]["sonar"], false);
+        // the sonar cleared the cells around the player, across the edges too
+        let row: String = (0..15)
+            .map(|x| match is_known(&reports[1], x, 3) {
+                true => '1',
+                false => '0',
+            })
+            .collect();
+        assert_eq!(row, "111100000000111");
+        assert_eq!(reports[2]["item"]["compass"], "up");
+        assert_eq!(reports[2]["hint"], Value::Null);
+        assert_eq!(reports[3]["last_command"], "metal_detector");
+        assert_eq!(reports[3]["item"]["metal_detector"], false);
+        assert_eq!(reports[3]["status"], "playing");
+        assert_eq!(reports[4]["error"], "invalid_command");
+    }
+
+    #[test]
+    fn json_counts_only_the_turns_that_change_the_game() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        test_board.player_coordinates = Position { x: 1, y: 2 };
+        test_board.treasure_coordinates = Position { x: 14, y: 14 };
+        let reports = play(&mut test_board, "r\np6\nh\nk\nl\np\ns\nr\n");
+
+        for report in &reports[1..7] {
+            assert_eq!(report["turn"], 0);
+        }
+        assert_eq!(reports[7]["last_command"], "search");
+        assert_eq!(reports[7]["turn"], 1);
+        assert_eq!(reports[8]["turn"], 1);
+    }
+
+    #[test]
+    fn json_refuses_the_export() {
+        let mut test_board = Board::new(GameSettings::get_default_settings());
+        let reports = play(&mut test_board, "x\ns\n");
+
+        assert_eq!(reports[1]["error"], "invalid_command");
+        assert_eq!(reports[1]["code"], 2);
+        // the refused export wasn't a turn
+        assert_eq!(reports[2]["turn"], 1);
+    }
+
+    #[test]
+    fn error_codes_are_stable() {
+        assert_eq!(
+            BoardError::InvalidMove(ErrorContext::default()).code(),
+            "invalid_move"
+        );
+        assert_eq!(
+            BoardError::InvalidCommand(ErrorContext::default()).code(),
+            "invalid_command"
+        );
+        assert_eq!(
+            BoardError::InvalidFormat(ErrorContext::default()).code(),
+            "invalid_format"
+        );
+        assert_eq!(
+            BoardError::FailedParse(ErrorContext::default()).code(),
+            "failed_parse"
+        );
+        assert_eq!(BoardError::Not2Dimensional(1).code(), "not_2_dimensional");
+        assert_eq!(BoardError::TooManyArguments(3).code(), "too_many_arguments");
+    }
+}
@@ -0,0 +1,394 @@
+use super::{input, parser, Board, BoardError, ErrorContext, FromStr, Geometry, Position, Zmove};
+use std::{fmt, fs, io};
+
+/// The shapes of the moves a player is allowed to do
+///
+/// # Variants
+/// * `Classic` - anything inside the reach square, the original rule
+/// * `King` - one step in any of the 8 directions
+/// * `Knight` - the chess L jumps, two steps then one step aside
+/// * `Rook` - straight lines up to the reach
+/// * `Bishop` - diagonal lines up to the reach
+/// * `Custom` - a list of `(dx, dy)` offsets, loaded from a file
+#[derive(Debug, PartialEq, Clone)]
+pub enum MoveSet {
+    Classic,
+    King,
+    Knight,
+    Rook,
+    Bishop,
+    Custom(Vec<(i64, i64)>),
+}
+
+impl MoveSet {
+    /// the offsets reachable in one move
+    ///
+    /// # Arguments
+    /// * `geometry` - the board, its reach bounds the lines and the square
+    ///
+    /// # Returns
+    /// * `Vec<(i64, i64)>` - every allowed `(dx, dy)`, `Classic` gives its whole square,
+    ///   it holds millions of them on a big reach so the checks never build it
+    pub fn offsets(&self, geometry: &Geometry) -> Vec<(i64, i64)> {
+        let max = geometry.reach as i64;
+        // the square of a side, the pieces only need a small one, the reach can be huge
+        let square =
+            |max: i64| (-max..=max).flat_map(move |dx| (-max..=max).map(move |dy| (dx, dy)));
+        match self {
+            MoveSet::Classic => square(max).collect(),
+            MoveSet::King => square(max.min(1))
+                .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+                .collect(),
+            MoveSet::Knight => square(max.min(2))
+                .filter(|&(dx, dy)| {
+                    (dx.abs(), dy.abs()) == (1, 2) || (dx.abs(), dy.abs()) == (2, 1)
+                })
+                .collect(),
+            // the lines are walked in the same order as the square
+            MoveSet::Rook => (-max..=max)
+                .flat_map(|dx| match dx {
+                    0 => (-max..=max)
+                        .filter(|&dy| dy != 0)
+                        .map(|dy| (0, dy))
+                        .collect(),
+                    _ => vec![(dx, 0)],
+                })
+                .collect(),
+            MoveSet::Bishop => (-max..=max)
+                .filter(|&dx| dx != 0)
+                .flat_map(|dx| [(dx, -dx.abs()), (dx, dx.abs())])
+                .collect(),
+            MoveSet::Custom(offsets) => offsets.clone(),
+        }
+    }
+
+    /// tells if a move from a position to another one is allowed, through the torus edges too
+    ///
+    /// # Arguments
+    /// * `from` - the in board starting position
+    /// * `to` - the in board target position
+    /// * `geometry` - the board the move is done on
+    ///
+    /// # Returns
+    /// * `bool` - true if one of the offsets goes from `from` to `to`
+    pub fn is_legal(&self, from: &Position, to: &Position, geometry: &Geometry) -> bool {
+        match self {
+            // the original rule, kept as it was
+            MoveSet::Classic => Position::is_dist_legal(from.get_xy_dists(to), geometry),
+            _ => self.offsets(geometry).iter().any(|offset| {
+                Board::coordinate_modulo(
+                    (from.to_i64().0 + offset.0, from.to_i64().1 + offset.1),
+                    geometry,
+                ) == *to
+            }),
+        }
+    }
+
+    /// tells if a zmove fits this move set, its vector has to be one of the offsets
+    ///
+    /// # Arguments
+    /// * `zmove` - the zmove to check
+    /// * `geometry` - the board, see `offsets`
+    ///
+    /// # Returns
+    /// * `bool` - true if the zmove is allowed
+    pub fn allows_zmove(&self, zmove: &Zmove, geometry: &Geometry) -> bool {
+        let (dx, dy) = zmove.get_vector();
+        match self {
+            // inside the reach square, like `is_legal`
+            MoveSet::Classic => dx.abs().max(dy.abs()) <= geometry.reach as i64,
+            _ => self.offsets(geometry).contains(&(dx, dy)),
+        }
+    }
+
+    /// the zmoves allowed by this move set, grouped by numpad direction
+    ///
+    /// # Arguments
+    /// * `geometry` - the board, see `offsets`
+    ///
+    /// # Returns
+    /// * `Vec<(u32, Vec<u32>)>` - each numpad direction with its allowed speeds, directions without any are left out
+    pub fn zmove_speeds(&self, geometry: &Geometry) -> Vec<(u32, Vec<u32>)> {
+        [7, 8, 9, 4, 6, 1, 2, 3]
+            .iter()
+            .map(|&direction| {
+                let speeds = (1..=Board::MOVE_MAX_DISTANCE)
+                    .filter(|&speed| match Zmove::new(direction, speed) {
+                        Ok(zmove) => self.allows_zmove(&zmove, geometry),
+                        Err(_) => false,
+                    })
+                    .collect::<Vec<u32>>();
+                (direction, speeds)
+            })
+            .filter(|(_, speeds)| !speeds.is_empty())
+            .collect()
+    }
+
+    /// checks the offsets of a custom move set written by hand, in a profile or a game code
+    /// an offset longer than the biggest board side only goes around it, and a huge one would overflow a position
+    ///
+    /// # Arguments
+    /// * `offsets` - the offsets to check
+    ///
+    /// # Returns
+    /// * `Ok` - if every offset is at most `MAX_BOARD_SIDE` long on both axes
+    /// * `Err(String)` - telling the first offset too long
+    pub fn check_offsets(offsets: &[(i64, i64)]) -> Result<(), String> {
+        let max = Board::MAX_BOARD_SIDE as u64;
+        match offsets
+            .iter()
+            .find(|(dx, dy)| dx.unsigned_abs() > max || dy.unsigned_abs() > max)
+        {
+            Some((dx, dy)) => Err(format!(
+                "the offset [{}, {}] is longer than the biggest board side {}",
+                dx, dy, max
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// loads a custom move set, one `dx,dy` offset per line
+    /// the offsets are parsed like the teleport values, `+1,-0x2` is fine,
+    /// empty lines and lines starting with `#` are ignored
+    ///
+    /// # Arguments
+    /// * `path` - the file to load
+    ///
+    /// # Returns
+    /// * `MoveSet::Custom` - the loaded move set
+    /// * `Err` - if the file can't be read, a line is wrong, or there is no offset, see `check_offsets`
+    pub fn from_file(path: &str) -> io::Result<MoveSet> {
+        let content = fs::read_to_string(path)?;
+        let mut offsets: Vec<(i64, i64)> = Vec::new();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |message: String| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}, {}", index + 1, message),
+                )
+            };
+            let offset =
+                MoveSet::parse_offset(line).map_err(|err| invalid(input::format_error(&err)))?;
+            MoveSet::check_offsets(&[offset]).map_err(invalid)?;
+            if !offsets.contains(&offset) {
+                offsets.push(offset);
+            }
+        }
+
+        match offsets.is_empty() {
+            true => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the file has no offset",
+            )),
+            false => Ok(MoveSet::Custom(offsets)),
+        }
+    }
+
+    /// parses one `dx,dy` offset of a custom move set file
+    ///
+    /// # Arguments
+    /// * `s` - the line
+    ///
+    /// # Returns
+    /// * `(i64, i64)` - the offset
+    /// * `BoardError` - like the teleport targets, if the line isn't a pair of signed numbers
+    fn parse_offset(s: &str) -> Result<(i64, i64), BoardError> {
+        let input = s.replace(' ', "");
+        let tokens = parser::tokenize_target(&input)?;
+        let values = parser::split_pair(&input, &tokens)?;
+        let parse = |(value, column): (&str, usize)| {
+            parser::parse_signed(value).map_err(|err| parser::relocate(err, &input, column))
+        };
+        Ok((parse(values[0])?, parse(values[1])?))
+    }
+}
+
+impl FromStr for MoveSet {
+    type Err = BoardError;
+
+    /// the move sets that can be chosen by name, the custom one is loaded with `from_file`
+    ///
+    /// # Arguments
+    /// * `s` - the name of the move set
+    ///
+    /// # Returns
+    /// * `MoveSet` - the named move set
+    /// * `BoardError::InvalidCommand` - if the name is unknown
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let clean_s = s.trim().to_lowercase();
+        match clean_s.as_str() {
+            "classic" | "c" => Ok(MoveSet::Classic),
+            "king" | "k" => Ok(MoveSet::King),
+            "knight" | "n" => Ok(MoveSet::Knight),
+            "rook" | "r" => Ok(MoveSet::Rook),
+            "bishop" | "b" => Ok(MoveSet::Bishop),
+            _ => Err(BoardError::InvalidCommand(
+                ErrorContext::new(
+                    "This move set is not recognised, use classic, king, knight, rook or bishop",
+                )
+                .with_input(&clean_s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MoveSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveSet::Classic => write!(f, "classic"),
+            MoveSet::King => write!(f, "king"),
+            MoveSet::Knight => write!(f, "knight"),
+            MoveSet::Rook => write!(f, "rook"),
+            MoveSet::Bishop => write!(f, "bishop"),
+            MoveSet::Custom(offsets) => write!(f, "custom ({} offsets)", offsets.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matches::assert_matches;
+
+    #[test]
+    fn offsets_of_each_move_set() {
+        let geometry = Geometry::default();
+        assert_eq!(MoveSet::King.offsets(&geometry).len(), 8);
+        assert_eq!(MoveSet::Knight.offsets(&geometry).len(), 8);
+        assert!(MoveSet::Knight.offsets(&geometry).contains(&(-1, 2)));
+        assert_eq!(
+            MoveSet::Rook.offsets(&geometry).len(),
+            4 * Board::MOVE_MAX_DISTANCE as usize
+        );
+        assert_eq!(
+            MoveSet::Bishop.offsets(&geometry).len(),
+            4 * Board::MOVE_MAX_DISTANCE as usize
+        );
+        assert!(!MoveSet::Rook.offsets(&geometry).contains(&(0, 0)));
+
+        // the lines are the ones of the square, in the same order
+        let square: Vec<(i64, i64)> = MoveSet::Classic.offsets(&geometry);
+        for (move_set, rule) in [
+            (
+                MoveSet::Rook,
+                (|(dx, dy): (i64, i64)| (dx == 0) != (dy == 0)) as fn((i64, i64)) -> bool,
+            ),
+            (MoveSet::Bishop, |(dx, dy)| dx != 0 && dx.abs() == dy.abs()),
+        ] {
+            let expected: Vec<(i64, i64)> = square.iter().copied().filter(|&o| rule(o)).collect();
+            assert_eq!(move_set.offsets(&geometry), expected);
+        }
+        // a short reach cuts the knight
+        let short = Geometry::new(15, 15, 1, 2).unwrap();
+        assert!(MoveSet::Knight.offsets(&short).is_empty());
+        assert_eq!(MoveSet::King.offsets(&short).len(), 8);
+    }
+
+    #[test]
+    fn legal_moves_wrap() {
+        let geometry = Geometry::default();
+        let corner = Position { x: 0, y: 0 };
+        assert!(MoveSet::Knight.is_legal(&corner, &Position { x: 14, y: 2 }, &geometry));
+        assert!(!MoveSet::Knight.is_legal(&corner, &Position { x: 1, y: 1 }, &geometry));
+        assert!(MoveSet::King.is_legal(&corner, &Position { x: 14, y: 14 }, &geometry));
+        assert!(!MoveSet::King.is_legal(&corner, &corner, &geometry));
+        assert!(MoveSet::Rook.is_legal(&corner, &Position { x: 0, y: 11 }, &geometry));
+        assert!(!MoveSet::Rook.is_legal(&corner, &Position { x: 1, y: 11 }, &geometry));
+        assert!(MoveSet::Bishop.is_legal(&corner, &Position { x: 12, y: 3 }, &geometry));
+        assert!(!MoveSet::Bishop.is_legal(&corner, &Position { x: 0, y: 3 }, &geometry));
+
+        // the classic rule is the old one
+        assert!(MoveSet::Classic.is_legal(&corner, &Position { x: 4, y: 11 }, &geometry));
+        assert!(MoveSet::Classic.is_legal(&corner, &corner, &geometry));
+        assert!(!MoveSet::Classic.is_legal(&corner, &Position { x: 5, y: 0 }, &geometry));
+
+        let custom = MoveSet::Custom(vec![(3, -1)]);
+        assert!(custom.is_legal(&corner, &Position { x: 3, y: 14 }, &geometry));
+        assert!(!custom.is_legal(&corner, &Position { x: 12, y: 1 }, &geometry));
+    }
+
+    #[test]
+    fn zmoves_follow_the_move_set() {
+        let geometry = Geometry::default();
+        assert!(MoveSet::Knight.zmove_speeds(&geometry).is_empty());
+        assert_eq!(
+            MoveSet::Rook.zmove_speeds(&geometry),
+            vec![
+                (8, vec![1, 2, 3, 4]),
+                (4, vec![1, 2, 3, 4]),
+                (6, vec![1, 2, 3, 4]),
+                (2, vec![1, 2, 3, 4]),
+            ]
+        );
+        assert_eq!(MoveSet::King.zmove_speeds(&geometry).len(), 8);
+        assert!(MoveSet::King
+            .zmove_speeds(&geometry)
+            .iter()
+            .all(|(_, s)| *s == vec![1]));
+        assert_eq!(MoveSet::Classic.zmove_speeds(&geometry).len(), 8);
+
+        assert!(MoveSet::Bishop.allows_zmove(&Zmove::new(9, 3).unwrap(), &geometry));
+        assert!(!MoveSet::Bishop.allows_zmove(&Zmove::new(8, 3).unwrap(), &geometry));
+
+        // the reach square of the biggest board would hold 400M offsets
+        let huge = Geometry::new(Board::MAX_BOARD_SIDE, Board::MAX_BOARD_SIDE, 9999, 0).unwrap();
+        assert_eq!(
+            MoveSet::Classic.zmove_speeds(&huge),
+            MoveSet::Classic.zmove_speeds(&geometry)
+        );
+        let short = Geometry::new(15, 15, 2, 2).unwrap();
+        assert!(MoveSet::Classic.allows_zmove(&Zmove::new(9, 2).unwrap(), &short));
+        assert!(!MoveSet::Classic.allows_zmove(&Zmove::new(9, 3).unwrap(), &short));
+    }
+
+    #[test]
+    fn long_offsets_are_refused() {
+        assert_eq!(MoveSet::check_offsets(&[(10_000, -10_000), (0, 1)]), Ok(()));
+        assert!(MoveSet::check_offsets(&[(0, 1), (10_001, 0)]).is_err());
+        assert!(MoveSet::check_offsets(&[(i64::MIN, 0)]).is_err());
+    }
+
+    #[test]
+    fn move_set_from_str() {
+        assert_eq!(MoveSet::from_str(" Knight ").unwrap(), MoveSet::Knight);
+        assert_eq!(MoveSet::from_str("b").unwrap(), MoveSet::Bishop);
+        assert_matches!(
+            MoveSet::from_str("queen").unwrap_err(),
+            BoardError::InvalidCommand(_)
+        );
+    }
+
+    #[test]
+    fn custom_move_set_from_file() {
+        let path = std::env::temp_dir().join("treasure_move_set_test.txt");
+        let path = path.to_str().unwrap();
+
+        fs::write(path, "# a camel\n3,1\n\n-3, 1\n(+0x3,-1)\n3,1\n").unwrap();
+        assert_eq!(
+            MoveSet::from_file(path).unwrap(),
+            MoveSet::Custom(vec![(3, 1), (-3, 1), (3, -1)])
+        );
+
+        fs::write(path, "3,1\n3,a\n").unwrap();
+        let err = MoveSet::from_file(path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 2, E04"));
+
+        // it would be saved in a profile or a game code that can't be loaded back
+        fs::write(path, "3,1\n10001,0\n").unwrap();
+        assert_eq!(
+            MoveSet::from_file(path).unwrap_err().to_string(),
+            "line 2, the offset [10001, 0] is longer than the biggest board side 10000"
+        );
+
+        fs::write(path, "# nothing\n").unwrap();
+        assert!(MoveSet::from_file(path).is_err());
+
+        fs::remove_file(path).unwrap();
+    }
+}
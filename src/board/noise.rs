@@ -0,0 +1,356 @@
+use super::{Board, BoardError, ErrorContext, FromStr, Geometry, Position, Xoshiro256StarStar};
+use serde::{Deserialize, Serialize};
+
+/// How the distance hints are blurred in the expert mode, the hints are exact without it
+///
+/// each hint is drawn with the board rng, so a seed always gives the same hints:
+/// a lie first, then some noise, the hint is exact the rest of the time
+///
+/// # Attributes
+/// * `spread` - how far a noisy hint can be from the real distance, it's `±spread`
+/// * `noise_percent` - the odds a hint is noisy, in percents
+/// * `lie_percent` - the odds a hint is any distance at all, in percents
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HintNoise {
+    pub spread: u32,
+    pub noise_percent: u32,
+    pub lie_percent: u32,
+}
+
+impl HintNoise {
+    /// builds a hint noise, the odds of a noisy hint and of a lie can't add up over 100%
+    ///
+    /// # Arguments
+    /// * `spread` - how far a noisy hint can be from the real distance
+    /// * `noise_percent` - the odds a hint is noisy
+    /// * `lie_percent` - the odds a hint is a lie
+    ///
+    /// # Returns
+    /// * `HintNoise` - the checked noise
+    /// * `Err(String)` - telling which value is wrong
+    pub fn new(spread: u32, noise_percent: u32, lie_percent: u32) -> Result<HintNoise, String> {
+        if noise_percent.saturating_add(lie_percent) > 100 {
+            return Err(format!(
+                "the noise {}% and the lies {}% add up over 100%",
+                noise_percent, lie_percent
+            ));
+        }
+        Ok(HintNoise {
+            spread,
+            noise_percent,
+            lie_percent,
+        })
+    }
+
+    /// a noisy hint lands here, it stays a distance a failed search can give
+    fn clamp(dist: u32, offset: i64, max_dist: u32) -> u32 {
+        (dist as i64 + offset).clamp(1, max_dist as i64) as u32
+    }
+
+    /// blurs the hint of a failed search
+    ///
+    /// # Arguments
+    /// * `dist` - the real distance to the treasure, at least 1
+    /// * `max_dist` - the farthest a cell can be on the board, see `Geometry::max_spawn_distance`
+    /// * `rng` - the board rng
+    ///
+    /// # Returns
+    /// * `u32` - the hint told to the player, between 1 and `max_dist`
+    pub fn perturb(&self, dist: u32, max_dist: u32, rng: &mut Xoshiro256StarStar) -> u32 {
+        let roll = rng.below(100);
+        if roll < self.lie_percent {
+            1 + rng.below(max_dist)
+        } else if roll < self.lie_percent + self.noise_percent {
+            let offset = rng.below(2 * self.spread + 1) as i64 - self.spread as i64;
+            HintNoise::clamp(dist, offset, max_dist)
+        } else {
+            dist
+        }
+    }
+
+    /// the odds `perturb` gives a hint, the solver weighs the cells with it
+    ///
+    /// # Arguments
+    /// * `hint` - the hint the player was told
+    /// * `dist` - the distance a cell is from the search
+    /// * `max_dist` - the farthest a cell can be on the board
+    ///
+    /// # Returns
+    /// * `f64` - the odds of this hint if the treasure is on that cell
+    pub fn likelihood(&self, hint: u32, dist: u32, max_dist: u32) -> f64 {
+        if hint == 0 || hint > max_dist {
+            return 0.0;
+        }
+        let spread = self.spread as i64;
+        let lie = self.lie_percent as f64 / max_dist as f64;
+        let noisy = (-spread..=spread)
+            .filter(|&offset| HintNoise::clamp(dist, offset, max_dist) == hint)
+            .count() as f64
+            * self.noise_percent as f64
+            / (2 * spread + 1) as f64;
+        let exact = match hint == dist {
+            true => (100 - self.lie_percent - self.noise_percent) as f64,
+            false => 0.0,
+        };
+        (lie + noisy + exact) / 100.0
+    }
+}
+
+impl FromStr for HintNoise {
+    type Err = BoardError;
+
+    /// reads a hint noise written `spread,noise,lie`, like `1,30,10`
+    ///
+    /// # Arguments
+    /// * `s` - the three numbers, the percents without the `%`
+    ///
+    /// # Returns
+    /// * `HintNoise` - the checked noise
+    /// * `BoardError::TooManyArguments` - if there aren't three numbers
+    /// * `BoardError::FailedParse` - if a number isn't a positive integer
+    /// * `BoardError::InvalidFormat` - if the odds add up over 100%
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values: Vec<&str> = s.split(',').map(str::trim).collect();
+        if values.len() != 3 {
+            return Err(BoardError::TooManyArguments(values.len()));
+        }
+        let numbers = values
+            .iter()
+            .map(|value| {
+                value.parse::<u32>().map_err(|_| {
+                    BoardError::FailedParse(
+                        ErrorContext::new("The spread and the odds are positive integers")
+                            .with_input(value),
+                    )
+                })
+            })
+            .collect::<Result<Vec<u32>, BoardError>>()?;
+        HintNoise::new(numbers[0], numbers[1], numbers[2]).map_err(|message| {
+            BoardError::InvalidFormat(ErrorContext::new(&message).with_input(s.trim()))
+        })
+    }
+}
+
+/// sums the weights of some cells and finds the heaviest one, see `Board::candidate_summary`
+///
+/// # Arguments
+/// * `cells` - the weighed cells, the first one wins a tie
+/// * `weigh` - the weight of a cell, see `Board::candidate_weigher`
+///
+/// # Returns
+/// * `Some((f64, Position, f64))` - the total weight, the heaviest cell and its weight
+/// * `None` - if every cell weighs 0
+fn summarize(
+    cells: impl Iterator<Item = Position>,
+    weigh: impl Fn(&Position) -> f64,
+) -> Option<(f64, Position, f64)> {
+    let (total, best) = cells.fold((0.0, None), |(total, best), position| {
+        let weight = weigh(&position);
+        let best = match best {
+            Some((_, best_weight)) if best_weight >= weight => best,
+            _ if weight > 0.0 => Some((position, weight)),
+            _ => best,
+        };
+        (total + weight, best)
+    });
+    best.map(|(position, weight)| (total, position, weight))
+}
+
+/// the solver, it weighs every cell the treasure can still be on with the hints told so far
+impl Board {
+    /// weighs a cell with the hints told so far, the weights aren't normalized, see `candidate_summary`
+    ///
+    /// every unsearched cell is as likely at first, then each distance hint weighs them,
+    /// with the exact hints it's a plain deduction and a cell is in or out,
+    /// with the noise no cell is ruled out for good unless it's too far from every noisy hint
+    /// the directions and the items are left out, they only clear cells
+    ///
+    /// # Returns
+    /// * `impl Fn(&Position) -> f64` - the weight of a cell, 0 if it can't hold the treasure
+    fn candidate_weigher(&self) -> impl Fn(&Position) -> f64 + '_ {
+        let max_dist = Geometry::max_spawn_distance(self.geometry.width, self.geometry.height);
+        // the exact hints are a noise that never happens
+        let noise = self.hint_noise.unwrap_or(HintNoise {
+            spread: 0,
+            noise_percent: 0,
+            lie_percent: 0,
+        });
+
+        move |position| {
+            if self.tracker.is_searched(position) {
+                return 0.0;
+            }
+            let mut weight = 1.0;
+            for (searched, record) in self.tracker.searches() {
+                if let Some(hint) = record.distance {
+                    let dist = Position::get_shortest_dist(
+                        searched.get_xy_dists(position),
+                        &self.geometry,
+                    );
+                    weight *= noise.likelihood(hint, dist, max_dist);
+                }
+                // with the exact hints most cells are ruled out by the first one
+                if weight == 0.0 {
+                    break;
+                }
+            }
+            weight
+        }
+    }
+
+    /// the cells at the distance told by the first exact hint, the treasure is on one of them
+    ///
+    /// # Returns
+    /// * `Some(Vec<Position>)` - the ring, column after column from the bottom left
+    /// * `None` - if the hints are noisy or none was told, any cell can hold the treasure
+    fn hint_ring(&self) -> Option<Vec<Position>> {
+        if self.hint_noise.is_some() {
+            return None;
+        }
+        let (center, hint) = self
+            .tracker
+            .searches()
+            .iter()
+            .find_map(|(searched, record)| record.distance.map(|hint| (searched, hint)))?;
+        let radius = hint as i64;
+        let (x, y) = center.to_i64();
+        let height = self.geometry.height as u64;
+        // on a small board the ring wraps over itself, the cells are sorted to remove the doubles
+        let mut indexes: Vec<u64> = (-radius..=radius)
+            .flat_map(|d| [(d, -radius), (d, radius), (-radius, d), (radius, d)])
+            .map(|(dx, dy)| {
+                let position = Board::coordinate_modulo((x + dx, y + dy), &self.geometry);
+                position.x as u64 * height + position.y as u64
+            })
+            .collect();
+        indexes.sort_unstable();
+        indexes.dedup();
+        Some(
+            indexes
+                .into_iter()
+                .map(|index| Position {
+                    x: (index / height) as u32,
+                    y: (index % height) as u32,
+                })
+                .collect(),
+        )
+    }
+
+    /// weighs the whole board, the cells are only weighed and never kept so even the biggest board fits,
+    /// once an exact hint is told only its ring is weighed, see `hint_ring`
+    ///
+    /// # Returns
+    /// * `Some((f64, Position, f64))` - the total weight, the most likely cell and its weight,
+    ///   the first one from the bottom left column after column on a tie
+    /// * `None` - if no cell can hold the treasure
+    pub(super) fn candidate_summary(&self) -> Option<(f64, Position, f64)> {
+        let weigh = self.candidate_weigher();
+        match self.hint_ring() {
+            Some(ring) => summarize(ring.into_iter(), weigh),
+            None => summarize(
+                (0..self.geometry.width)
+                    .flat_map(|x| (0..self.geometry.height).map(move |y| Position { x, y })),
+                weigh,
+            ),
+        }
+    }
+
+    /// the odds of each cell in view to hold the treasure, knowing the hints
+    /// the whole board is weighed but only the cells of the `viewport` are given, like the drawing
+    /// they don't grow with the board, so they add up to 1 only when the board is drawn whole
+    ///
+    /// # Arguments
+    /// * `total` - the weight of the whole board, see `candidate_summary`
+    ///
+    /// # Returns
+    /// * `Vec<(Position, f64)>` - the cells in view that can hold the treasure and their odds
+    pub(super) fn candidate_weights(&self, total: f64) -> Vec<(Position, f64)> {
+        let weigh = self.candidate_weigher();
+        let (columns, rows) = self.viewport();
+        columns
+            .iter()
+            .flat_map(|&x| rows.iter().map(move |&y| Position { x, y }))
+            .map(|position| {
+                let weight = weigh(&position);
+                (position, weight / total)
+            })
+            .filter(|(_, odds)| *odds > 0.0)
+            .collect()
+    }
+
+    /// the cell of the whole board the treasure is most likely on, see `candidate_summary`
+    ///
+    /// # Returns
+    /// * `Some((Position, f64))` - the cell and its odds
+    /// * `None` - if no cell can hold the treasure
+    pub(super) fn best_candidate(&self) -> Option<(Position, f64)> {
+        self.candidate_summary()
+            .map(|(total, position, weight)| (position, weight / total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matches::assert_matches;
+    use rand::SeedableRng;
+
+    #[test]
+    fn noise_limits() {
+        assert!(HintNoise::new(2, 60, 40).is_ok());
+        assert_eq!(
+            HintNoise::new(2, 70, 40).unwrap_err(),
+            "the noise 70% and the lies 40% add up over 100%"
+        );
+        assert_eq!(
+            HintNoise::from_str(" 1, 30 ,10 ").unwrap(),
+            HintNoise::new(1, 30, 10).unwrap()
+        );
+        assert_matches!(
+            HintNoise::from_str("1,30").unwrap_err(),
+            BoardError::TooManyArguments(2)
+        );
+        assert_matches!(
+            HintNoise::from_str("1,-30,10").unwrap_err(),
+            BoardError::FailedParse(_)
+        );
+        assert_matches!(
+            HintNoise::from_str("1,90,20").unwrap_err(),
+            BoardError::InvalidFormat(_)
+        );
+    }
+
+    #[test]
+    fn likelihoods_add_up() {
+        let noise = HintNoise::new(2, 40, 20).unwrap();
+        for dist in 1..=7 {
+            let total: f64 = (0..=8).map(|hint| noise.likelihood(hint, dist, 7)).sum();
+            assert!((total - 1.0).abs() < 1e-9, "{} for {}", total, dist);
+        }
+        // without noise the only hint is the distance
+        let exact = HintNoise::new(3, 0, 0).unwrap();
+        assert_eq!(exact.likelihood(4, 4, 7), 1.0);
+        assert_eq!(exact.likelihood(5, 4, 7), 0.0);
+    }
+
+    #[test]
+    fn perturb_follows_the_likelihood() {
+        // the solver only works if it weighs the hints like they are drawn
+        // a chi-squared test on 7 hints, 6 degrees of freedom, 22.46 is the 0.1% threshold
+        let noise = HintNoise::new(2, 40, 20).unwrap();
+        let mut rng = Xoshiro256StarStar::seed_from_u64(44);
+        let mut counts = [0u32; 8];
+        for _ in 0..70_000 {
+            counts[noise.perturb(2, 7, &mut rng) as usize] += 1;
+        }
+        assert_eq!(counts[0], 0);
+        let chi_squared: f64 = (1..=7)
+            .map(|hint| {
+                let expected = 70_000.0 * noise.likelihood(hint, 2, 7);
+                (counts[hint as usize] as f64 - expected).powi(2) / expected
+            })
+            .sum();
+        assert!(chi_squared < 22.46, "{:?} doesn't fit", counts);
+    }
+}
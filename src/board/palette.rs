@@ -0,0 +1,222 @@
+use super::export::color_to_rgb;
+use super::{Board, BoardError, Color, ErrorContext, FromStr};
+use std::fmt;
+
+/// The colors of the water, the searched cells and the player
+///
+/// the standard one draws the searched cells in the player color on blue water,
+/// the others are made for the color blindnesses, they lean on the brightness more than on the hue
+///
+/// # Variants
+/// * `Standard` - the usual blue water
+/// * `Deuteranopia` - for the players who don't see the green well, the most common one
+/// * `Protanopia` - for the players who don't see the red well, the reds look darker
+/// * `Tritanopia` - for the players who don't see the blue well, blue and green look alike
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Palette {
+    Standard,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+/// the relative luminance of a color, from 0 for black to 1 for white, like in the WCAG
+fn luminance(color: &Color) -> f64 {
+    let linear = |value: u8| {
+        let value = value as f64 / 255.0;
+        match value <= 0.03928 {
+            true => value / 12.92,
+            false => ((value + 0.055) / 1.055).powf(2.4),
+        }
+    };
+    let (r, g, b) = color_to_rgb(color);
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+/// the contrast ratio of two colors, from 1 for the same brightness to 21 for black on white
+///
+/// # Arguments
+/// * `first` `second` - the colors, in any order
+///
+/// # Returns
+/// * `f64` - the ratio of the lighter one to the darker one
+pub fn contrast_ratio(first: &Color, second: &Color) -> f64 {
+    let (first, second) = (luminance(first), luminance(second));
+    (first.max(second) + 0.05) / (first.min(second) + 0.05)
+}
+
+impl Palette {
+    /// every palette, in the order of the game codes
+    pub const ALL: [Palette; 4] = [
+        Palette::Standard,
+        Palette::Deuteranopia,
+        Palette::Protanopia,
+        Palette::Tritanopia,
+    ];
+
+    /// the color of the water tiles
+    pub fn water(&self) -> Color {
+        match self {
+            Palette::Standard => Board::WATER_COLOR,
+            Palette::Deuteranopia | Palette::Protanopia => Color::Rgb(0, 114, 178),
+            Palette::Tritanopia => Color::Rgb(0, 90, 110),
+        }
+    }
+
+    /// the color of the searched cells, see `Tracker`
+    ///
+    /// # Arguments
+    /// * `player_color` - the standard palette draws them in the player color
+    pub fn searched(&self, player_color: Color) -> Color {
+        match self {
+            Palette::Standard => player_color,
+            Palette::Deuteranopia => Color::Rgb(230, 159, 0),
+            Palette::Protanopia => Color::Rgb(240, 228, 66),
+            Palette::Tritanopia => Color::Rgb(213, 94, 0),
+        }
+    }
+
+    /// the player color the palette comes with, choosing the palette switches to it
+    pub fn player_color(&self) -> Color {
+        match self {
+            Palette::Standard => Board::DEFAULT_PLAYER_COLOR,
+            Palette::Deuteranopia | Palette::Protanopia => Color::Rgb(204, 121, 167),
+            Palette::Tritanopia => Color::Rgb(255, 140, 160),
+        }
+    }
+
+    /// checks the player color stands out from the water and from the board
+    /// the contrast is a ratio of brightness, see `contrast_ratio`
+    ///
+    /// # Arguments
+    /// * `color` - the player color
+    ///
+    /// # Returns
+    /// * `Ok(None)` - if the color is easy to see
+    /// * `Ok(Some(String))` - a warning, the color can be used but the game is harder
+    /// * `Err(String)` - if the color can't be seen on the water, below `CONTRAST_REFUSED`
+    pub fn check_player_color(&self, color: &Color) -> Result<Option<String>, String> {
+        let water = contrast_ratio(color, &self.water());
+        let board = contrast_ratio(color, &Board::BOARD_COLOR);
+        if water < Board::CONTRAST_REFUSED {
+            Err(format!(
+                "the contrast with the water is {:.2}:1, you couldn't be seen on it",
+                water
+            ))
+        } else if water < Board::CONTRAST_WARNED {
+            Ok(Some(format!(
+                "the contrast with the water is only {:.2}:1, you will be hard to see",
+                water
+            )))
+        } else if board < Board::CONTRAST_WARNED {
+            Ok(Some(format!(
+                "the contrast with the board is only {:.2}:1, you will be hard to tell from the fog",
+                board
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl FromStr for Palette {
+    type Err = BoardError;
+
+    /// the palettes are chosen by name, or by the first letter of it
+    ///
+    /// # Arguments
+    /// * `s` - the name of the palette
+    ///
+    /// # Returns
+    /// * `Palette` - the named palette
+    /// * `BoardError::InvalidCommand` - if the name is unknown
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let clean_s = s.trim().to_lowercase();
+        match clean_s.as_str() {
+            "standard" | "s" => Ok(Palette::Standard),
+            "deuteranopia" | "d" => Ok(Palette::Deuteranopia),
+            "protanopia" | "p" => Ok(Palette::Protanopia),
+            "tritanopia" | "t" => Ok(Palette::Tritanopia),
+            _ => Err(BoardError::InvalidCommand(
+                ErrorContext::new(
+                    "This palette is not recognised, use standard, deuteranopia, protanopia or tritanopia",
+                )
+                .with_input(&clean_s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Palette::Standard => write!(f, "standard"),
+            Palette::Deuteranopia => write!(f, "deuteranopia"),
+            Palette::Protanopia => write!(f, "protanopia"),
+            Palette::Tritanopia => write!(f, "tritanopia"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matches::assert_matches;
+
+    #[test]
+    fn contrasts_follow_the_wcag() {
+        assert!((contrast_ratio(&Color::Black, &Color::Rgb(255, 255, 255)) - 21.0).abs() < 1e-9);
+        assert_eq!(contrast_ratio(&Color::Red, &Color::Red), 1.0);
+        assert_eq!(
+            contrast_ratio(&Color::Red, &Color::Blue),
+            contrast_ratio(&Color::Blue, &Color::Red)
+        );
+    }
+
+    #[test]
+    fn unreadable_colors_are_refused() {
+        let standard = Palette::Standard;
+        // the closer to blue the harder the game
+        assert!(standard.check_player_color(&Color::Blue).is_err());
+        assert!(standard.check_player_color(&Color::Rgb(0, 0, 200)).is_err());
+        assert_eq!(
+            standard.check_player_color(&Board::DEFAULT_PLAYER_COLOR),
+            Ok(None)
+        );
+        assert_eq!(standard.check_player_color(&Color::Green), Ok(None));
+        assert!(standard
+            .check_player_color(&Color::White)
+            .unwrap()
+            .unwrap()
+            .contains("board"));
+        assert!(standard
+            .check_player_color(&Color::Rgb(50, 50, 50))
+            .unwrap()
+            .unwrap()
+            .contains("water"));
+        // a fine color on blue water can be lost on another one
+        assert!(Palette::Deuteranopia
+            .check_player_color(&Board::DEFAULT_PLAYER_COLOR)
+            .is_err());
+    }
+
+    #[test]
+    fn palettes_are_readable() {
+        for palette in Palette::ALL {
+            assert_eq!(
+                palette.check_player_color(&palette.player_color()),
+                Ok(None),
+                "{}",
+                palette
+            );
+            let searched = palette.searched(palette.player_color());
+            assert!(contrast_ratio(&searched, &palette.water()) >= Board::CONTRAST_WARNED);
+            assert_eq!(Palette::from_str(&palette.to_string()).unwrap(), palette);
+        }
+        assert_eq!(Palette::from_str(" D ").unwrap(), Palette::Deuteranopia);
+        assert_matches!(
+            Palette::from_str("sepia").unwrap_err(),
+            BoardError::InvalidCommand(_)
+        );
+    }
+}
@@ -0,0 +1,590 @@
+use super::{BoardError, ErrorContext};
+
+/// A token of a number pair like `(12,0xc)`
+///
+/// # Variants
+/// * `Open` - an opening bracket, `(` or `[`
+/// * `Close` - a closing bracket, `)` or `]`
+/// * `Comma` - the separator between the numbers
+/// * `Text` - a run of number chars: digits, hex letters, `x` and `-`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TokenKind<'a> {
+    Open(char),
+    Close(char),
+    Comma,
+    Text(&'a str),
+}
+
+/// A token and the char index where it starts in the input
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Token<'a> {
+    pub kind: TokenKind<'a>,
+    pub column: usize,
+}
+
+/// A successfully parsed number pair
+///
+/// # Attributes
+/// * `input` - the input without its spaces, the columns refer to it
+/// * `values` - the two numbers
+/// * `columns` - the char index where each number starts
+#[derive(Debug, PartialEq)]
+pub struct NumberPair {
+    pub input: String,
+    pub values: (u32, u32),
+    pub columns: (usize, usize),
+}
+
+/// tells if a char can be part of a number `Text` token
+fn is_number_char(c: char) -> bool {
+    c.is_ascii_hexdigit() || c == 'x' || c == '-'
+}
+
+/// tells if a char can be part of a teleport target value, like `~-0b11` or `+0o7`
+fn is_target_char(c: char) -> bool {
+    is_number_char(c) || c == 'o' || c == '+' || c == '~'
+}
+
+/// gives the closing bracket that goes with an opening one
+fn closing_of(open: char) -> char {
+    match open {
+        '(' => ')',
+        _ => ']',
+    }
+}
+
+/// moves an error pointing inside a part of the input so it points inside the whole input
+///
+/// # Arguments
+/// * `err` - the error, its column is relative to the part
+/// * `input` - the whole input
+/// * `offset` - where the part starts in the whole input
+pub fn relocate(err: BoardError, input: &str, offset: usize) -> BoardError {
+    let inner_column = err.context().and_then(|c| c.column).unwrap_or(0);
+    err.located(input, offset + inner_column)
+}
+
+/// cuts an input without spaces into tokens
+///
+/// the only chars accepted are the brackets, the comma, the hex digits, `x` and `-`,
+/// anything else is refused right here
+///
+/// # Arguments
+/// * `s` - the input, already without spaces
+///
+/// # Returns
+/// * `Vec<Token>` - the tokens in order
+/// * `BoardError::InvalidFormat` - pointing at the first unexpected char
+pub fn tokenize(s: &str) -> Result<Vec<Token<'_>>, BoardError> {
+    tokenize_with(s, is_number_char)
+}
+
+/// cuts a teleport target without spaces into tokens
+/// on top of the number chars, `o`, `+` and `~` are accepted, see `Target`
+///
+/// # Arguments
+/// * `s` - the input, already without spaces
+///
+/// # Returns
+/// * `Vec<Token>` - the tokens in order
+/// * `BoardError::InvalidFormat` - pointing at the first unexpected char
+pub fn tokenize_target(s: &str) -> Result<Vec<Token<'_>>, BoardError> {
+    tokenize_with(s, is_target_char)
+}
+
+/// cuts an input without spaces into tokens, the chars of the `Text` tokens are chosen
+///
+/// # Arguments
+/// * `s` - the input, already without spaces
+/// * `is_text_char` - tells if a char can be part of a `Text` token
+///
+/// # Returns
+/// * `Vec<Token>` - the tokens in order
+/// * `BoardError::InvalidFormat` - pointing at the first unexpected char
+fn tokenize_with(s: &str, is_text_char: fn(char) -> bool) -> Result<Vec<Token<'_>>, BoardError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut text_start: Option<usize> = None;
+
+    // any non ascii char is refused, so before it the byte index is also the column
+    for (column, c) in s.char_indices() {
+        if is_text_char(c) {
+            if text_start.is_none() {
+                text_start = Some(column);
+            }
+            continue;
+        }
+
+        // the text run is over
+        if let Some(start) = text_start.take() {
+            tokens.push(Token {
+                kind: TokenKind::Text(&s[start..column]),
+                column: start,
+            });
+        }
+
+        let kind = match c {
+            '(' | '[' => TokenKind::Open(c),
+            ')' | ']' => TokenKind::Close(c),
+            ',' => TokenKind::Comma,
+            _ => {
+                return Err(BoardError::InvalidFormat(
+                    ErrorContext::new(&format!(
+                        "Unexpected character '{}', only numbers, ',' and brackets are accepted",
+                        c
+                    ))
+                    .with_input(s)
+                    .at_column(column),
+                ))
+            }
+        };
+        tokens.push(Token { kind, column });
+    }
+
+    if let Some(start) = text_start {
+        tokens.push(Token {
+            kind: TokenKind::Text(&s[start..]),
+            column: start,
+        });
+    }
+    Ok(tokens)
+}
+
+/// parses a single unsigned number, either decimal or hexadecimal like `0xCAFE`
+///
+/// a number containing a `x` is hexadecimal, its `0x` prefix is removed
+/// the columns of the errors are relative to `s`
+///
+/// # Arguments
+/// * `s` - the number, without spaces
+///
+/// # Returns
+/// * `u32` - the number
+/// * `BoardError::FailedParse` - if the number is empty, negative, has a bad digit or is too big
+pub fn parse_number(s: &str) -> Result<u32, BoardError> {
+    let failed = |message: &str, column: usize| {
+        BoardError::FailedParse(
+            ErrorContext::new(message)
+                .with_input(s)
+                .at_column(column)
+                .expecting(0, u32::MAX as i64),
+        )
+    };
+
+    if s.is_empty() {
+        return Err(failed("A number is missing here", 0));
+    }
+    if s.starts_with('-') {
+        return Err(failed("Negative numbers are not accepted", 0));
+    }
+
+    let is_hex = s.contains('x');
+    let digits = match is_hex {
+        true => s.trim_start_matches("0x"),
+        false => s,
+    };
+    let digits_column = s.len() - digits.len();
+
+    if digits.is_empty() {
+        return Err(failed(
+            "This hexadecimal integer has no digit after 0x",
+            digits_column,
+        ));
+    }
+
+    let bad_digit = digits.char_indices().find(|(_, c)| match is_hex {
+        true => !c.is_ascii_hexdigit(),
+        false => !c.is_ascii_digit(),
+    });
+    if let Some((index, c)) = bad_digit {
+        let message = match (is_hex, c) {
+            (_, '-') => "Unexpected '-', a number can't be split or negative".to_string(),
+            (false, _) if c.is_ascii_hexdigit() => format!(
+                "This decimal integer failed to be recognised, invalid digit '{}', hexadecimal integers start with 0x.",
+                c
+            ),
+            (true, _) => format!(
+                "This hexadecimal integer failed to be recognised, invalid digit '{}'.",
+                c
+            ),
+            (false, _) => format!(
+                "This decimal integer failed to be recognised, invalid digit '{}'.",
+                c
+            ),
+        };
+        return Err(failed(&message, digits_column + index));
+    }
+
+    // only the overflow is left
+    let radix = match is_hex {
+        true => 16,
+        false => 10,
+    };
+    u32::from_str_radix(digits, radix).map_err(|_| match is_hex {
+        true => failed("This hexadecimal integer is too big", 0),
+        false => failed("This decimal integer is too big", 0),
+    })
+}
+
+/// parses a single unsigned number, like `parse_number` but binary `0b101` and octal `0o17` are accepted too
+///
+/// # Arguments
+/// * `s` - the number, without spaces
+///
+/// # Returns
+/// * `u32` - the number
+/// * `BoardError::FailedParse` - if the number is empty, negative, has a bad digit or is too big
+pub fn parse_literal(s: &str) -> Result<u32, BoardError> {
+    let (digits, radix, name) = match s.get(..2) {
+        Some("0b") => (&s[2..], 2, "binary"),
+        Some("0o") => (&s[2..], 8, "octal"),
+        _ => return parse_number(s),
+    };
+    let failed = |message: &str, column: usize| {
+        BoardError::FailedParse(
+            ErrorContext::new(message)
+                .with_input(s)
+                .at_column(column)
+                .expecting(0, u32::MAX as i64),
+        )
+    };
+
+    if digits.is_empty() {
+        return Err(failed(
+            &format!("This {} integer has no digit after {}", name, &s[..2]),
+            2,
+        ));
+    }
+    if let Some((index, c)) = digits.char_indices().find(|(_, c)| !c.is_digit(radix)) {
+        return Err(failed(
+            &format!(
+                "This {} integer failed to be recognised, invalid digit '{}'.",
+                name, c
+            ),
+            2 + index,
+        ));
+    }
+    u32::from_str_radix(digits, radix)
+        .map_err(|_| failed(&format!("This {} integer is too big", name), 0))
+}
+
+/// parses a signed number, `+3`, `-0x2` or `0b11`, the number itself is read by `parse_literal`
+///
+/// # Arguments
+/// * `s` - the number, without spaces
+///
+/// # Returns
+/// * `i64` - the number
+/// * `BoardError::FailedParse` - if the number is empty, has a bad digit or is too big
+pub fn parse_signed(s: &str) -> Result<i64, BoardError> {
+    let (digits, sign) = match s.chars().next() {
+        Some('+') => (&s[1..], 1),
+        Some('-') => (&s[1..], -1),
+        _ => (s, 1),
+    };
+    let sign_length = s.len() - digits.len();
+    if sign_length > 0 && digits.starts_with(['+', '-']) {
+        return Err(BoardError::FailedParse(
+            ErrorContext::new("Only one sign is accepted")
+                .with_input(s)
+                .at_column(sign_length),
+        ));
+    }
+    parse_literal(digits)
+        .map(|number| sign * number as i64)
+        .map_err(|err| relocate(err, s, sign_length))
+}
+
+/// parses a pair of numbers, optionally surrounded by `()` or `[]`, like `(12,0xc)`
+///
+/// the spaces are removed first, all the columns refer to the input without spaces
+/// the checks are done in this order, so the errors are the same as the old regex ones:
+/// the chars and the brackets, then the number of values, then each number
+///
+/// # Arguments
+/// * `s` - the input as the user typed it
+///
+/// # Returns
+/// * `NumberPair` - the two numbers and where they are
+/// * `BoardError::InvalidFormat` - if a char is unexpected or the brackets are wrong
+/// * `BoardError::Not2Dimensional` - if there aren't 2 values separated with ,
+/// * `BoardError::FailedParse` - if a number is empty, negative, has a bad digit or is too big
+pub fn parse_number_pair(s: &str) -> Result<NumberPair, BoardError> {
+    let input = s.trim().replace(' ', "");
+    let values = split_pair(&input, &tokenize(&input)?)?;
+
+    let first = parse_number(values[0].0).map_err(|err| relocate(err, &input, values[0].1))?;
+    let second = parse_number(values[1].0).map_err(|err| relocate(err, &input, values[1].1))?;
+
+    Ok(NumberPair {
+        values: (first, second),
+        columns: (values[0].1, values[1].1),
+        input,
+    })
+}
+
+/// checks the brackets around a pair and cuts it into its two values
+///
+/// # Arguments
+/// * `input` - the input without spaces
+/// * `tokens` - the tokens of `input`
+///
+/// # Returns
+/// * `[(&str, usize); 2]` - each value and the column where it starts, a value can be empty
+/// * `BoardError::InvalidFormat` - if the input is empty or the brackets are wrong
+/// * `BoardError::Not2Dimensional` - if there aren't 2 values separated with ,
+pub fn split_pair<'a>(
+    input: &str,
+    tokens: &[Token<'a>],
+) -> Result<[(&'a str, usize); 2], BoardError> {
+    let invalid = |message: &str, column: usize| {
+        BoardError::InvalidFormat(
+            ErrorContext::new(message)
+                .with_input(input)
+                .at_column(column),
+        )
+    };
+
+    if tokens.is_empty() {
+        return Err(invalid(
+            "Nothing was entered, a pair like '12,13' is expected",
+            0,
+        ));
+    }
+
+    // the brackets, only one pair around everything is accepted
+    let mut body: &[Token] = tokens;
+    if let TokenKind::Open(open) = tokens[0].kind {
+        let last = tokens[tokens.len() - 1];
+        match last.kind {
+            TokenKind::Close(close) if tokens.len() > 1 && close == closing_of(open) => {
+                body = &tokens[1..tokens.len() - 1]
+            }
+            TokenKind::Close(close) if tokens.len() > 1 => {
+                return Err(invalid(
+                    &format!("Mismatched brackets, '{}' is closed by '{}'", open, close),
+                    last.column,
+                ))
+            }
+            _ => {
+                // either something follows the closing bracket, or there is none
+                return Err(
+                    match tokens
+                        .iter()
+                        .position(|t| matches!(t.kind, TokenKind::Close(_)))
+                    {
+                        Some(close_index) => invalid(
+                            "Nothing is accepted after the closing bracket",
+                            tokens[close_index + 1].column,
+                        ),
+                        None => invalid(
+                            &format!("The bracket '{}' is never closed", open),
+                            input.chars().count(),
+                        ),
+                    },
+                );
+            }
+        }
+    }
+    if let Some(stray) = body
+        .iter()
+        .find(|t| matches!(t.kind, TokenKind::Open(_) | TokenKind::Close(_)))
+    {
+        let message = match stray.kind {
+            TokenKind::Close(close) => format!("The bracket '{}' was never opened", close),
+            _ => "Only one pair of brackets around the values is accepted".to_string(),
+        };
+        return Err(invalid(&message, stray.column));
+    }
+    if body.is_empty() {
+        return Err(invalid("There is nothing between the brackets", 1));
+    }
+
+    // the values, an empty value is at the column of what follows it
+    let mut values: Vec<(&str, usize)> = Vec::new();
+    let mut current: Option<(&str, usize)> = None;
+    for token in body {
+        match token.kind {
+            TokenKind::Text(text) => current = Some((text, token.column)),
+            _ => values.push(current.take().unwrap_or(("", token.column))), // a comma
+        }
+    }
+    let end_column = match tokens[tokens.len() - 1].kind {
+        TokenKind::Close(_) => tokens[tokens.len() - 1].column,
+        _ => input.chars().count(),
+    };
+    values.push(current.unwrap_or(("", end_column)));
+
+    match values[..] {
+        [first, second] => Ok([first, second]),
+        _ => Err(BoardError::Not2Dimensional(values.len())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use regex::Regex;
+
+    /// the regex that was used before this parser, it's the oracle of the fuzz test
+    const OLD_PARENTHESIS_REGEX: &str =
+        r"^([(]{1}[0-9,a-fxA-F\-]+[)]{1}$|[\[]{1}[0-9,a-fxA-F\-]+[\]]{1}$|[0-9,a-fxA-F\-]+$)";
+
+    /// the old regex based parsing, kept as it was
+    fn old_parse_number_pair(s: &str) -> Result<(u32, u32), BoardError> {
+        let no_space_s = s.trim().replace(' ', "");
+        if !Regex::new(OLD_PARENTHESIS_REGEX)
+            .unwrap()
+            .is_match(no_space_s.as_str())
+        {
+            return Err(BoardError::InvalidFormat(ErrorContext::default()));
+        }
+        let clean_s = no_space_s
+            .trim_start_matches(['(', '['])
+            .trim_end_matches([')', ']']);
+        let number_value: Vec<&str> = clean_s.split(',').collect();
+        if number_value.len() != 2 {
+            return Err(BoardError::Not2Dimensional(number_value.len()));
+        }
+        let old_number = |s: &str| -> Result<u32, BoardError> {
+            let parsed = match s.contains('x') {
+                true => u32::from_str_radix(s.trim_start_matches("0x"), 16),
+                false => s.parse::<u32>(),
+            };
+            parsed.map_err(|_| BoardError::FailedParse(ErrorContext::default()))
+        };
+        Ok((old_number(number_value[0])?, old_number(number_value[1])?))
+    }
+
+    fn column_of(err: BoardError) -> Option<usize> {
+        err.context().and_then(|c| c.column)
+    }
+
+    #[test]
+    fn tokenize_pair() {
+        assert_eq!(
+            tokenize("(1,0xc]").unwrap(),
+            vec![
+                Token {
+                    kind: TokenKind::Open('('),
+                    column: 0
+                },
+                Token {
+                    kind: TokenKind::Text("1"),
+                    column: 1
+                },
+                Token {
+                    kind: TokenKind::Comma,
+                    column: 2
+                },
+                Token {
+                    kind: TokenKind::Text("0xc"),
+                    column: 3
+                },
+                Token {
+                    kind: TokenKind::Close(']'),
+                    column: 6
+                },
+            ]
+        );
+        assert_eq!(column_of(tokenize("12,1g").unwrap_err()), Some(4));
+    }
+
+    #[test]
+    fn valid_number_pair() {
+        let pair = parse_number_pair(" [ 12 , 0xc ] ").unwrap();
+        assert_eq!(pair.values, (12, 12));
+        assert_eq!(pair.columns, (1, 4));
+        assert_eq!(pair.input, "[12,0xc]");
+        assert_eq!(parse_number_pair("3,4").unwrap().values, (3, 4));
+    }
+
+    #[test]
+    fn brackets_errors() {
+        let err = parse_number_pair("(3,4]").unwrap_err();
+        assert_eq!(
+            err.context().unwrap().message,
+            "Mismatched brackets, '(' is closed by ']'"
+        );
+        assert_eq!(column_of(err), Some(4));
+
+        let err = parse_number_pair("(3,4").unwrap_err();
+        assert_eq!(
+            err.context().unwrap().message,
+            "The bracket '(' is never closed"
+        );
+        assert_eq!(column_of(err), Some(4));
+
+        let err = parse_number_pair("3,4)").unwrap_err();
+        assert_eq!(
+            err.context().unwrap().message,
+            "The bracket ')' was never opened"
+        );
+        assert_eq!(column_of(err), Some(3));
+
+        matches::assert_matches!(
+            parse_number_pair("()").unwrap_err(),
+            BoardError::InvalidFormat(_)
+        );
+    }
+
+    #[test]
+    fn number_errors() {
+        // empty component, pointed at what follows it
+        let err = parse_number_pair("(3,)").unwrap_err();
+        assert_eq!(err.context().unwrap().message, "A number is missing here");
+        assert_eq!(column_of(err), Some(3));
+
+        let err = parse_number_pair("-3,4").unwrap_err();
+        assert_eq!(
+            err.context().unwrap().message,
+            "Negative numbers are not accepted"
+        );
+        assert_eq!(column_of(err), Some(0));
+
+        // the old regex accepted this one, now the second '-' is pointed at
+        let err = parse_number_pair("3-4-5,1").unwrap_err();
+        assert_eq!(column_of(err), Some(1));
+
+        let err = parse_number_pair("1,4294967296").unwrap_err();
+        assert_eq!(
+            err.context().unwrap().message,
+            "This decimal integer is too big"
+        );
+        assert_eq!(column_of(err), Some(2));
+
+        let err = parse_number_pair("1,0xAG").unwrap_err();
+        matches::assert_matches!(err, BoardError::InvalidFormat(_));
+        assert_eq!(column_of(err), Some(5));
+
+        let err = parse_number_pair("1,1a").unwrap_err();
+        assert_eq!(column_of(err), Some(3));
+    }
+
+    #[test]
+    fn fuzz_against_old_regex() {
+        // the same inputs must be accepted with the same values, and refused with the same variant
+        let alphabet: Vec<char> = "0123456789abcdefABCDEFxX-,()[] g".chars().collect();
+        let mut rng = StdRng::seed_from_u64(30);
+
+        for _ in 0..20000 {
+            let length = rng.gen_range(0..10);
+            let s: String = (0..length)
+                .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                .collect();
+
+            let old = old_parse_number_pair(&s);
+            let new = parse_number_pair(&s).map(|pair| pair.values);
+            match (old, new) {
+                (Ok(old_values), Ok(new_values)) => assert_eq!(old_values, new_values, "{}", s),
+                (Err(old_err), Err(new_err)) => assert_eq!(
+                    std::mem::discriminant(&old_err),
+                    std::mem::discriminant(&new_err),
+                    "{:?}",
+                    s
+                ),
+                (old, new) => panic!("{:?}: old {:?}, new {:?}", s, old, new),
+            }
+        }
+    }
+}
@@ -1,11 +1,12 @@
-use super::{assert_matches, Board, BoardError, FromStr, Regex};
+use super::{parser, BoardError, FromStr, Geometry};
+use serde::Serialize;
 use std::fmt;
 
 /// The representation of a position
 /// this position can be outside the board
 /// this is rarely verified in the Position level
 /// It should be berified at Board level when necessary
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct Position {
     pub x: u32,
     pub y: u32,
@@ -51,19 +52,41 @@ impl Position {
     ///
     /// # Arguments
     /// `dist` - the u32 pair of non-torus distances
+    /// `geometry` - the size of the torus
     ///
     /// # Returns
     /// `u32` - the shortest distance in the board format
-    pub fn get_shortest_dist(dist: (u32, u32)) -> u32 {
-        let modular_inverse_dist_x =
-            i64::abs(dist.0 as i64 - Board::DEFAULT_BOARD_WIDTH as i64) as u32;
-        let modular_inverse_dist_y =
-            i64::abs(dist.1 as i64 - Board::DEFAULT_BOARD_HEIGHT as i64) as u32;
+    pub fn get_shortest_dist(dist: (u32, u32), geometry: &Geometry) -> u32 {
+        let modular_inverse_dist_x = i64::abs(dist.0 as i64 - geometry.width as i64) as u32;
+        let modular_inverse_dist_y = i64::abs(dist.1 as i64 - geometry.height as i64) as u32;
         let min_dist_x = std::cmp::min(dist.0, modular_inverse_dist_x);
         let min_dist_y = std::cmp::min(dist.1, modular_inverse_dist_y);
         std::cmp::max(min_dist_x, min_dist_y)
     }
 
+    /// the shortest vector from this position to another one on the torus
+    /// each axis goes the short way around, when both ways are as long it goes right or up
+    ///
+    /// # Arguments
+    /// `other` - the position the vector goes to
+    /// `geometry` - the size of the torus
+    ///
+    /// # Returns
+    /// `(i64, i64)` - the x and y of the vector, y goes up
+    pub fn torus_vector(&self, other: &Position, geometry: &Geometry) -> (i64, i64) {
+        let shortest = |from: u32, to: u32, side: u32| {
+            let forward = (to as i64 - from as i64).rem_euclid(side as i64);
+            match forward * 2 <= side as i64 {
+                true => forward,
+                false => forward - side as i64,
+            }
+        };
+        (
+            shortest(self.x, other.x, geometry.width),
+            shortest(self.y, other.y, geometry.height),
+        )
+    }
+
     /// this exploits the torus properties of the board
     /// and therefore is pretty weird
     ///
@@ -76,57 +99,21 @@ impl Position {
     ///
     /// # Arguments
     /// * `xy_dist` - this is **not** a position, but the x and y distance between 2 positions
+    /// * `geometry` - the size of the torus and the reach of a move
     ///
     /// # Returns
     /// `bool` - true if distance is legal
-    pub fn is_dist_legal(xy_dist: (u32, u32)) -> bool {
-        let board_width_height = (Board::DEFAULT_BOARD_WIDTH, Board::DEFAULT_BOARD_HEIGHT); // this line is due to an unclean fix of an abandonned feature
+    pub fn is_dist_legal(xy_dist: (u32, u32), geometry: &Geometry) -> bool {
+        let board_width_height = (geometry.width, geometry.height);
         if xy_dist.0 > board_width_height.0 || xy_dist.1 > board_width_height.1 {
             panic!("The dist is bigger than the board, this should never happen");
         }
-        if (xy_dist.0 <= Board::MOVE_MAX_DISTANCE
-            || xy_dist.0 >= board_width_height.0 - Board::MOVE_MAX_DISTANCE)
-            && (xy_dist.1 <= Board::MOVE_MAX_DISTANCE
-                || xy_dist.1 >= board_width_height.1 - Board::MOVE_MAX_DISTANCE)
+        if (xy_dist.0 <= geometry.reach || xy_dist.0 >= board_width_height.0 - geometry.reach)
+            && (xy_dist.1 <= geometry.reach || xy_dist.1 >= board_width_height.1 - geometry.reach)
         {
             return true;
         }
-        return false;
-    }
-
-    /// This returns either an error or the integer corresponding to the number in the str
-    /// It works for hex and dec, hex have to be in format: `0xCAFE`
-    ///
-    /// # Arguments
-    ///
-    /// * `s` - the str reference from which we get an integer
-    ///
-    /// # Returns
-    ///
-    /// * `u32` representing the number
-    /// * `BoardError::FailedParse` - if the number is negative or not a number
-    pub fn parse_dec_or_hex(s: &str) -> Result<u32, BoardError> {
-        if s.contains('x') {
-            // hex
-            let s_hex = s.trim_start_matches("0x");
-
-            match u32::from_str_radix(s_hex, 16) {
-                Ok(num) => Ok(num),
-                Err(err) => Err(BoardError::FailedParse(format!(
-                    "This hexadecimal integer failed to be recognised, {}.",
-                    err.to_string()
-                ))),
-            }
-        } else {
-            // dec
-            match u32::from_str(s) {
-                Ok(num) => Ok(num),
-                Err(err) => Err(BoardError::FailedParse(format!(
-                    "This decimal integer failed to be recognised, {}.",
-                    err.to_string()
-                ))),
-            }
-        }
+        false
     }
 }
 
@@ -156,152 +143,123 @@ impl FromStr for Position {
     ///
     /// # Returns
     /// * `Position` - the position we got from str
-    /// * `BoardError::InvalidFormat` - if format is incorrect for () []
+    /// * `BoardError::InvalidFormat` - if a char is unexpected or the brackets are incorrect for () []
     /// * `BoardError::FailedParse` - if the number is negative or not a number
     /// * `BoardError::Not2Dimensional` - if there aren't 2 values separated with ,
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let no_space_s = s.trim().replace(|x| x == ' ', ""); //we got rid of spaces
-
-        if !Regex::new(Board::PARENTHESIS_REGEX)
-            .unwrap()
-            .is_match(no_space_s.as_str())
-        {
-            return Err(BoardError::InvalidFormat(
-                "Incorrect parenthesis format, please format your destination like this '12,13' '[12,0xc]' '(0x12,14)'".to_string(),
-            ));
-        }
-
-        let clean_s = no_space_s
-            .trim_start_matches(|p| p == '(' || p == '[')
-            .trim_end_matches(|p| p == ')' || p == ']')
-            .trim();
-
-        // numeric handling
-        let number_value: Vec<&str> = clean_s.split(',').collect(); // we get the strs
-
-        if number_value.len() != 2 {
-            // if not 2 dim
-            return Err(BoardError::Not2Dimensional(number_value.len()));
-        } else {
-            // 2 dim
-            let position_to_return = Position {
-                x: match Position::parse_dec_or_hex(number_value[0]) {
-                    Ok(int) => int,
-                    Err(err) => return Err(err),
-                },
-                y: match Position::parse_dec_or_hex(number_value[1]) {
-                    Ok(int) => int,
-                    Err(err) => return Err(err),
-                },
-            };
-            Ok(position_to_return)
-        }
+        let pair = parser::parse_number_pair(s)?;
+        Ok(Position {
+            x: pair.values.0,
+            y: pair.values.1,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::Board;
     use super::*;
+    use matches::assert_matches;
 
     #[test]
     fn valid_dist_expected() {
+        let geometry = Geometry::default();
         // logical expectation for legal distances
         assert!(
-            Position::is_dist_legal((Board::MOVE_MAX_DISTANCE, 0),),
+            Position::is_dist_legal((Board::MOVE_MAX_DISTANCE, 0), &geometry),
             "moving to max x dist should be legal"
         );
         assert!(
-            Position::is_dist_legal((0, Board::MOVE_MAX_DISTANCE),),
+            Position::is_dist_legal((0, Board::MOVE_MAX_DISTANCE), &geometry),
             "moving to max y dist should be legal"
         );
         assert!(
-            Position::is_dist_legal((Board::MOVE_MAX_DISTANCE, Board::MOVE_MAX_DISTANCE),),
+            Position::is_dist_legal(
+                (Board::MOVE_MAX_DISTANCE, Board::MOVE_MAX_DISTANCE),
+                &geometry
+            ),
             "max x and max y is legal"
         );
         assert!(
-            Position::is_dist_legal((Board::MOVE_MAX_DISTANCE / 2, Board::MOVE_MAX_DISTANCE / 2),),
+            Position::is_dist_legal(
+                (Board::MOVE_MAX_DISTANCE / 2, Board::MOVE_MAX_DISTANCE / 2),
+                &geometry
+            ),
             "somewhere in the middle is legal"
         );
 
         assert!(
-            Position::is_dist_legal((0, 0),),
+            Position::is_dist_legal((0, 0), &geometry),
             "not moving at all should be legal in legal distances"
         );
     }
 
     #[test]
     fn valid_dist_unexpected() {
+        let geometry = Geometry::default();
         // unexpected legal distances that are actually legal du to the torus properties of the board
 
         // x axis
         assert!(
             Position::is_dist_legal(
-                (Board::DEFAULT_BOARD_WIDTH, 0),
-            ),
+                (Board::DEFAULT_BOARD_WIDTH, 0), &geometry),
             "moving to left/right and doing a warp around and getting on same position (same as not moving)"
         );
 
         assert!(
             Position::is_dist_legal(
-                (Board::DEFAULT_BOARD_WIDTH-Board::MOVE_MAX_DISTANCE, 0),
-            ),
+                (Board::DEFAULT_BOARD_WIDTH-Board::MOVE_MAX_DISTANCE, 0), &geometry),
             "moving to left/right and doing a warp around and getting on the leftest/rightest after a warparound"
         );
 
         assert!(
             Position::is_dist_legal(
-                (Board::DEFAULT_BOARD_WIDTH-(Board::MOVE_MAX_DISTANCE/2), 0),
-            ),
+                (Board::DEFAULT_BOARD_WIDTH-(Board::MOVE_MAX_DISTANCE/2), 0), &geometry),
             "moving to left/right and doing a warp around and getting somwhere between the furthest left/right possible after a warp around and the right/left edge of the board"
         );
 
         // y axis
         assert!(
             Position::is_dist_legal(
-                (0, Board::DEFAULT_BOARD_HEIGHT),
-            ),
+                (0, Board::DEFAULT_BOARD_HEIGHT), &geometry),
                 "moving to up/down and doing a warp around and getting on same position (same as not moving)"
         );
 
         assert!(
             Position::is_dist_legal(
-                (0, Board::DEFAULT_BOARD_HEIGHT-Board::MOVE_MAX_DISTANCE),
-            ),
+                (0, Board::DEFAULT_BOARD_HEIGHT-Board::MOVE_MAX_DISTANCE), &geometry),
                 "moving to up/down and doing a warp around and getting on the downest/upest after a warparound"
             );
 
         assert!(
             Position::is_dist_legal(
-                (0, Board::DEFAULT_BOARD_HEIGHT-(Board::MOVE_MAX_DISTANCE/2)),
-            ),
+                (0, Board::DEFAULT_BOARD_HEIGHT-(Board::MOVE_MAX_DISTANCE/2)), &geometry),
                 "moving to up/down and doing a warp around and getting somwhere between the furthest down/up possible after a warp around and the down/up edge of the board"
            );
 
         // x and y
         assert!(
             Position::is_dist_legal(
-                (Board::DEFAULT_BOARD_WIDTH, Board::DEFAULT_BOARD_HEIGHT),
-            ),
+                (Board::DEFAULT_BOARD_WIDTH, Board::DEFAULT_BOARD_HEIGHT), &geometry),
                 "moving to opposite quadrant and doing a warp around and getting on same position (same as not moving)"
         );
 
         assert!(
             Position::is_dist_legal(
-                (Board::DEFAULT_BOARD_WIDTH-Board::MOVE_MAX_DISTANCE, Board::DEFAULT_BOARD_HEIGHT-Board::MOVE_MAX_DISTANCE),
-            ),
+                (Board::DEFAULT_BOARD_WIDTH-Board::MOVE_MAX_DISTANCE, Board::DEFAULT_BOARD_HEIGHT-Board::MOVE_MAX_DISTANCE), &geometry),
                 "moving to opposite quadrant and doing a warp around and getting on the closest to start after a warparound"
             );
 
         assert!(
             Position::is_dist_legal(
-                (Board::DEFAULT_BOARD_WIDTH-(Board::MOVE_MAX_DISTANCE/2), Board::DEFAULT_BOARD_HEIGHT-(Board::MOVE_MAX_DISTANCE/2)),
-            ),
+                (Board::DEFAULT_BOARD_WIDTH-(Board::MOVE_MAX_DISTANCE/2), Board::DEFAULT_BOARD_HEIGHT-(Board::MOVE_MAX_DISTANCE/2)), &geometry),
                 "moving to opposite quadrant and doing a warp around and getting somwhere between the closest to start possible after a warp around and the edge of the board"
            );
     }
 
     #[test]
     fn invalid_dist() {
+        let geometry = Geometry::default();
         // warning, those test only work if the torus is sufficiently large, in such a way that there actually exists illegal distances
         // the board aslo needs to be a square for those to work
 
@@ -311,12 +269,15 @@ mod tests {
 
         // there are illegal positions in x and y
         assert!(
-            !Position::is_dist_legal((max_dist + 1, max_dist + 1)),
+            !Position::is_dist_legal((max_dist + 1, max_dist + 1), &geometry),
             "over max dist x and y should not be legal"
         );
 
         assert!(
-            !Position::is_dist_legal((board_width - (max_dist + 1), board_height - (max_dist + 1)),),
+            !Position::is_dist_legal(
+                (board_width - (max_dist + 1), board_height - (max_dist + 1)),
+                &geometry
+            ),
             "warparound over max dist x and y should not be legal"
         );
 
@@ -327,113 +288,67 @@ mod tests {
         // there are no illegal position in y
 
         assert!(
-            !Position::is_dist_legal((max_dist + 1, 0)),
+            !Position::is_dist_legal((max_dist + 1, 0), &geometry),
             "over max dist x should not be legal"
         );
 
         assert!(
-            !Position::is_dist_legal((board_width - (max_dist + 1), 0),),
+            !Position::is_dist_legal((board_width - (max_dist + 1), 0), &geometry),
             "warparound over max dist x should not be legal"
         );
 
         // else if !(board_height / 2 <= max_dist) && board_width / 2 <= max_dist
         // there are no illegal position in x
         assert!(
-            !Position::is_dist_legal((0, max_dist + 1),),
+            !Position::is_dist_legal((0, max_dist + 1), &geometry),
             "over max dist y should not be legal"
         );
 
         assert!(
-            !Position::is_dist_legal((0, board_height - (max_dist + 1)),),
+            !Position::is_dist_legal((0, board_height - (max_dist + 1)), &geometry),
             "warparound over max dist y should not be legal"
         );
     }
 
     #[test]
     fn impossible_dist() {
+        let geometry = Geometry::default();
         // impossible values that should trigger a panic
-        assert!(std::panic::catch_unwind(|| Position::is_dist_legal((
-            0,
-            Board::DEFAULT_BOARD_HEIGHT + 1
-        ),))
+        assert!(std::panic::catch_unwind(|| Position::is_dist_legal(
+            (0, Board::DEFAULT_BOARD_HEIGHT + 1),
+            &geometry
+        ))
         .is_err());
 
-        assert!(std::panic::catch_unwind(|| Position::is_dist_legal((
-            Board::DEFAULT_BOARD_WIDTH + 1,
-            0
-        ),))
+        assert!(std::panic::catch_unwind(|| Position::is_dist_legal(
+            (Board::DEFAULT_BOARD_WIDTH + 1, 0),
+            &geometry
+        ))
         .is_err());
 
-        assert!(std::panic::catch_unwind(|| Position::is_dist_legal((
-            Board::DEFAULT_BOARD_WIDTH + 1,
-            Board::DEFAULT_BOARD_HEIGHT + 1
-        ),))
+        assert!(std::panic::catch_unwind(|| Position::is_dist_legal(
+            (
+                Board::DEFAULT_BOARD_WIDTH + 1,
+                Board::DEFAULT_BOARD_HEIGHT + 1
+            ),
+            &geometry
+        ))
         .is_err());
 
         // impossible values that should trigger a panic
-        assert!(std::panic::catch_unwind(|| Position::is_dist_legal((0, u32::MAX),)).is_err());
-
-        assert!(std::panic::catch_unwind(|| Position::is_dist_legal((u32::MAX, 0),)).is_err());
-
         assert!(
-            std::panic::catch_unwind(|| Position::is_dist_legal((u32::MAX, u32::MAX),)).is_err()
+            std::panic::catch_unwind(|| Position::is_dist_legal((0, u32::MAX), &geometry)).is_err()
         );
-    }
 
-    #[test]
-    fn valid_dec_from_str() {
-        assert_eq!(Position::parse_dec_or_hex("0x10").unwrap(), 16);
-        assert_eq!(Position::parse_dec_or_hex("0xA").unwrap(), 10);
-        assert_eq!(Position::parse_dec_or_hex("0xb").unwrap(), 11);
-        assert_eq!(Position::parse_dec_or_hex("10").unwrap(), 10);
-        assert_eq!(Position::parse_dec_or_hex("0").unwrap(), 0);
-        assert_eq!(Position::parse_dec_or_hex("0x0").unwrap(), 0);
-        assert_eq!(
-            Position::parse_dec_or_hex("4294967295").unwrap(),
-            4294967295
-        );
-        assert_eq!(
-            Position::parse_dec_or_hex("0xFFFFFFFF").unwrap(),
-            4294967295
-        );
-    }
-
-    #[test]
-    fn invalid_dec_from_str() {
-        assert_matches!(
-            Position::parse_dec_or_hex("A").unwrap_err(),
-            BoardError::FailedParse(_)
-        );
-        assert_matches!(
-            Position::parse_dec_or_hex("12-34").unwrap_err(),
-            BoardError::FailedParse(_)
-        );
-        assert_matches!(
-            Position::parse_dec_or_hex("x34").unwrap_err(),
-            BoardError::FailedParse(_)
-        );
-        assert_matches!(
-            Position::parse_dec_or_hex("-0x34").unwrap_err(),
-            BoardError::FailedParse(_)
-        );
-        assert_matches!(
-            Position::parse_dec_or_hex("-20").unwrap_err(),
-            BoardError::FailedParse(_)
-        ); // negative number are refused for u32
-        assert_matches!(
-            Position::parse_dec_or_hex("0xAG").unwrap_err(),
-            BoardError::FailedParse(_)
-        );
-        assert_matches!(
-            Position::parse_dec_or_hex("4294967296").unwrap_err(), // bigger than u32
-            BoardError::FailedParse(_)
-        );
-        assert_matches!(
-            Position::parse_dec_or_hex("0x100000000").unwrap_err(), // bigger than u32
-            BoardError::FailedParse(_)
+        assert!(
+            std::panic::catch_unwind(|| Position::is_dist_legal((u32::MAX, 0), &geometry)).is_err()
         );
 
-        assert!(i64::from_str("0x10").is_err()); // rust should handle this, but alas, doesn't
+        assert!(std::panic::catch_unwind(|| Position::is_dist_legal(
+            (u32::MAX, u32::MAX),
+            &geometry
+        ))
+        .is_err());
     }
 
     #[test]
@@ -613,4 +528,29 @@ mod tests {
             BoardError::Not2Dimensional(_)
         );
     }
+
+    #[test]
+    fn invalid_position_from_str_points_at_number() {
+        let err = Position::from_str("(3,0xfffffffff)").unwrap_err();
+        let context = err.context().unwrap();
+        assert_eq!(context.input.as_deref(), Some("(3,0xfffffffff)"));
+        assert_eq!(context.column, Some(3));
+
+        let err = Position::from_str("[-3, 4]").unwrap_err();
+        assert_eq!(err.context().unwrap().column, Some(1));
+        assert_eq!(err.to_string().lines().last(), Some("\t ^"));
+    }
+
+    #[test]
+    fn torus_vectors_go_the_short_way() {
+        let geometry = Geometry::default();
+        let pos = |x, y| Position { x, y };
+        assert_eq!(pos(1, 1).torus_vector(&pos(3, 0), &geometry), (2, -1));
+        assert_eq!(pos(1, 14).torus_vector(&pos(13, 0), &geometry), (-3, 1));
+        assert_eq!(pos(4, 4).torus_vector(&pos(4, 4), &geometry), (0, 0));
+        // an even side has a cell as far both ways
+        let even = Geometry::new(10, 4, 1, 2).unwrap();
+        assert_eq!(pos(0, 3).torus_vector(&pos(5, 1), &even), (5, 2));
+        assert_eq!(pos(5, 1).torus_vector(&pos(0, 3), &even), (5, 2));
+    }
 }
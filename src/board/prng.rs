@@ -0,0 +1,196 @@
+use rand::{Error, RngCore, SeedableRng};
+
+/// One step of SplitMix64, it turns any state into well mixed 64 bits
+/// used to expand a seed, and to derive a seed from something else like a date
+///
+/// # Arguments
+/// * `state` - the state, it is advanced by one step
+///
+/// # Returns
+/// * `u64` - the next output
+pub fn split_mix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// The random generator of the boards, xoshiro256** 1.0 by Blackman and Vigna
+///
+/// the algorithm is written here instead of taken from `rand`, whose `StdRng` may change
+/// with any release, so a seed gives the same board in every version of the game
+/// a `u64` seed is expanded into the 4 words of state with `split_mix64`, as advised by the authors
+/// the golden tests below lock the outputs, they must never be changed
+/// it isn't suitable for crypto, but this isn't crypto
+///
+/// # Attributes
+/// * `state` - the 256 bits of state, never all zero
+#[derive(Debug, PartialEq, Clone)]
+pub struct Xoshiro256StarStar {
+    state: [u64; 4],
+}
+
+impl Xoshiro256StarStar {
+    /// a number drawn uniformly below a bound, every value has exactly the same odds
+    ///
+    /// a plain `next_u32() % bound` favours the small values when `bound` isn't a power of two,
+    /// so the lowest `2^32 % bound` outputs are thrown away and drawn again,
+    /// what is left is a whole number of copies of `0..bound`
+    /// less than one draw in two is thrown away, whatever the bound
+    ///
+    /// # Arguments
+    /// * `bound` - the values are in `0..bound`, it can't be 0
+    ///
+    /// # Returns
+    /// * `u32` - the drawn value
+    pub fn below(&mut self, bound: u32) -> u32 {
+        let threshold = bound.wrapping_neg() % bound; // 2^32 % bound, without overflow
+        loop {
+            let value = self.next_u32();
+            if value >= threshold {
+                return value % bound;
+            }
+        }
+    }
+}
+
+impl RngCore for Xoshiro256StarStar {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32 // the high bits are the best ones
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+
+        result
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Xoshiro256StarStar {
+    type Seed = [u8; 32];
+
+    /// the state as 4 little endian words, an all zero state would only give zeros,
+    /// it's replaced by the state of the seed 0
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut state = [0u64; 4];
+        for (word, bytes) in state.iter_mut().zip(seed.chunks(8)) {
+            let mut word_bytes = [0u8; 8];
+            word_bytes.copy_from_slice(bytes);
+            *word = u64::from_le_bytes(word_bytes);
+        }
+        match state == [0; 4] {
+            true => Xoshiro256StarStar::seed_from_u64(0),
+            false => Xoshiro256StarStar { state },
+        }
+    }
+
+    /// the seed of the game settings, expanded with `split_mix64`
+    fn seed_from_u64(seed: u64) -> Self {
+        let mut split_mix_state = seed;
+        let mut state = [0u64; 4];
+        for word in state.iter_mut() {
+            *word = split_mix64(&mut split_mix_state);
+        }
+        Xoshiro256StarStar { state }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_mix64_reference_outputs() {
+        // the first outputs of the reference implementation for the seed 1234567
+        let mut state: u64 = 1234567;
+        assert_eq!(split_mix64(&mut state), 6457827717110365317);
+        assert_eq!(split_mix64(&mut state), 3203168211198807973);
+        assert_eq!(split_mix64(&mut state), 9817491932198370423);
+    }
+
+    #[test]
+    fn xoshiro_reference_outputs() {
+        // the state 1, 2, 3, 4 of the reference implementation
+        let mut rng = Xoshiro256StarStar {
+            state: [1, 2, 3, 4],
+        };
+        let outputs: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+        assert_eq!(outputs, vec![11520, 0, 1509978240, 1215971899390074240]);
+    }
+
+    #[test]
+    fn seeds_are_pinned() {
+        let outputs = |seed: u64| {
+            let mut rng = Xoshiro256StarStar::seed_from_u64(seed);
+            (rng.next_u64(), rng.next_u32())
+        };
+        assert_eq!(outputs(0), (11091344671253066420, 3211665272));
+        assert_eq!(outputs(2), (1884871951439679575, 3116073026));
+
+        let mut bytes = [0u8; 12];
+        Xoshiro256StarStar::seed_from_u64(2).fill_bytes(&mut bytes);
+        assert_eq!(bytes[..8], 1884871951439679575u64.to_le_bytes());
+
+        assert_eq!(
+            Xoshiro256StarStar::from_seed([0; 32]),
+            Xoshiro256StarStar::seed_from_u64(0)
+        );
+    }
+
+    #[test]
+    fn below_throws_the_biased_values_away() {
+        // 2^32 % 3 = 1, only the output 0 is thrown away
+        // the first 3 outputs of this state are below 2^32, so their high bits are 0
+        let mut rng = Xoshiro256StarStar {
+            state: [1, 2, 3, 4],
+        };
+        let mut skipped = rng.clone();
+        assert_eq!(rng.below(3), 283115520 % 3);
+        for _ in 0..4 {
+            skipped.next_u64();
+        }
+        assert_eq!(rng, skipped);
+
+        let mut rng = Xoshiro256StarStar::seed_from_u64(5);
+        assert!((0..1000).all(|_| rng.below(7) < 7));
+        assert!((0..100).all(|_| rng.below(1) == 0));
+        assert!((0..100).any(|_| rng.below(u32::MAX) > u32::MAX / 2));
+    }
+
+    #[test]
+    fn below_is_uniform() {
+        // a chi-squared test on 6 values, 5 degrees of freedom, 20.5 is the 0.1% threshold
+        let mut rng = Xoshiro256StarStar::seed_from_u64(40);
+        let mut counts = [0u32; 6];
+        for _ in 0..60_000 {
+            counts[rng.below(6) as usize] += 1;
+        }
+        let chi_squared: f64 = counts
+            .iter()
+            .map(|&count| (count as f64 - 10_000.0).powi(2) / 10_000.0)
+            .sum();
+        assert!(chi_squared < 20.5, "{:?} isn't uniform", counts);
+    }
+}
@@ -0,0 +1,288 @@
+use super::{parser, BoardError, ErrorContext, FromStr, Geometry, Position};
+
+/// One coordinate of a teleport target
+///
+/// # Variants
+/// * `Absolute` - a coordinate on the board, like `12` or `0b101`
+/// * `Relative` - an offset from the player, like `+3`, `-2`, `~-1` or `~` alone for 0
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Coordinate {
+    Absolute(u32),
+    Relative(i64),
+}
+
+/// A named place of the board
+///
+/// # Variants
+/// * `Here` - the player position
+/// * `Center` - the middle of the board
+/// * `Origin` - the `(0,0)` corner
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Anchor {
+    Here,
+    Center,
+    Origin,
+}
+
+/// Where the player wants to teleport, as typed in the move prompt
+/// nothing is resolved until the player position is known, see `Target::resolve`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Target {
+    Anchor(Anchor),
+    Pair(Coordinate, Coordinate),
+}
+
+impl Coordinate {
+    /// parses one value of a target pair
+    /// a leading `~` or sign makes the value relative, `~` alone is an offset of 0
+    /// the columns of the errors are relative to `s`
+    ///
+    /// # Arguments
+    /// * `s` - the value, without spaces
+    ///
+    /// # Returns
+    /// * `Coordinate` - the parsed value
+    /// * `BoardError::FailedParse` - if the number is missing, has a bad digit or is too big
+    fn parse(s: &str) -> Result<Coordinate, BoardError> {
+        let (rest, is_tilde) = match s.strip_prefix('~') {
+            Some(rest) => (rest, true),
+            None => (s, false),
+        };
+        if is_tilde && rest.is_empty() {
+            return Ok(Coordinate::Relative(0));
+        }
+        let offset = s.len() - rest.len(); // ascii only, the bytes are the columns
+
+        if let Some(index) = rest.find('~') {
+            return Err(BoardError::FailedParse(
+                ErrorContext::new("The '~' is only accepted at the start of a value")
+                    .with_input(s)
+                    .at_column(offset + index),
+            ));
+        }
+
+        let number = parser::parse_signed(rest).map_err(|err| parser::relocate(err, s, offset))?;
+        let is_signed = rest.starts_with(['+', '-']);
+        Ok(match is_tilde || is_signed {
+            true => Coordinate::Relative(number),
+            false => Coordinate::Absolute(number as u32), // without a sign it's a u32
+        })
+    }
+
+    /// the value of this coordinate once the player one is known, it can be out of the board
+    fn resolve(self, player: i64) -> i64 {
+        match self {
+            Coordinate::Absolute(value) => value as i64,
+            Coordinate::Relative(offset) => player + offset,
+        }
+    }
+}
+
+impl Target {
+    /// resolves the target from the player position
+    /// the result can be outside of the board, `Board::coordinate_modulo` wraps it
+    ///
+    /// # Arguments
+    /// * `player` - the position the relative values and `here` are taken from
+    /// * `geometry` - the board, its middle is the `center`
+    ///
+    /// # Returns
+    /// * `(i64, i64)` - the target, not wrapped yet
+    pub fn resolve(&self, player: &Position, geometry: &Geometry) -> (i64, i64) {
+        match self {
+            Target::Anchor(Anchor::Here) => player.to_i64(),
+            Target::Anchor(Anchor::Center) => {
+                ((geometry.width / 2) as i64, (geometry.height / 2) as i64)
+            }
+            Target::Anchor(Anchor::Origin) => (0, 0),
+            Target::Pair(x, y) => (x.resolve(player.to_i64().0), y.resolve(player.to_i64().1)),
+        }
+    }
+
+    /// the typed position, only if both values are absolute
+    /// used to warn the player when an absolute position is outside of the board
+    ///
+    /// # Returns
+    /// * `Some(Position)` - the position as typed
+    /// * `None` - if a value is relative or the target is an anchor
+    pub fn absolute_position(&self) -> Option<Position> {
+        match self {
+            Target::Pair(Coordinate::Absolute(x), Coordinate::Absolute(y)) => {
+                Some(Position { x: *x, y: *y })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = BoardError;
+
+    /// parses a teleport target, either an anchor or a pair like the positions
+    /// the pair values can be relative, `+3,-2` `~-1,~4`, and binary or octal, `0b101,0o17`
+    ///
+    /// # Arguments
+    /// * `s` - the str from which we get the target
+    ///
+    /// # Returns
+    /// * `Target` - the target we got from str
+    /// * `BoardError::InvalidFormat` - if a word isn't an anchor, a char is unexpected or the brackets are incorrect
+    /// * `BoardError::Not2Dimensional` - if there aren't 2 values separated with ,
+    /// * `BoardError::FailedParse` - if a value is missing, has a bad digit or is too big
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim().replace(' ', "");
+
+        match input.to_lowercase().as_str() {
+            "here" => return Ok(Target::Anchor(Anchor::Here)),
+            "center" | "centre" => return Ok(Target::Anchor(Anchor::Center)),
+            "origin" => return Ok(Target::Anchor(Anchor::Origin)),
+            word if !word.is_empty() && word.chars().all(|c| c.is_ascii_alphabetic()) => {
+                return Err(BoardError::InvalidFormat(
+                    ErrorContext::new("This anchor is not recognised, use here, center or origin")
+                        .with_input(&input)
+                        .at_column(0),
+                ))
+            }
+            _ => (),
+        }
+
+        let tokens = parser::tokenize_target(&input)?;
+        let values = parser::split_pair(&input, &tokens)?;
+        let parse = |(value, column): (&str, usize)| {
+            Coordinate::parse(value).map_err(|err| parser::relocate(err, &input, column))
+        };
+        Ok(Target::Pair(parse(values[0])?, parse(values[1])?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Board;
+    use super::*;
+    use matches::assert_matches;
+
+    fn column_of(err: BoardError) -> Option<usize> {
+        err.context().and_then(|c| c.column)
+    }
+
+    #[test]
+    fn valid_target_from_str() {
+        assert_eq!(
+            Target::from_str("12,0xc").unwrap(),
+            Target::Pair(Coordinate::Absolute(12), Coordinate::Absolute(12))
+        );
+        assert_eq!(
+            Target::from_str("+3,-2").unwrap(),
+            Target::Pair(Coordinate::Relative(3), Coordinate::Relative(-2))
+        );
+        assert_eq!(
+            Target::from_str("(~-1, ~4)").unwrap(),
+            Target::Pair(Coordinate::Relative(-1), Coordinate::Relative(4))
+        );
+        assert_eq!(
+            Target::from_str("~,3").unwrap(),
+            Target::Pair(Coordinate::Relative(0), Coordinate::Absolute(3))
+        );
+        assert_eq!(
+            Target::from_str("[0b101,0o17]").unwrap(),
+            Target::Pair(Coordinate::Absolute(5), Coordinate::Absolute(15))
+        );
+        assert_eq!(
+            Target::from_str("-0x2,~+0b1").unwrap(),
+            Target::Pair(Coordinate::Relative(-2), Coordinate::Relative(1))
+        );
+
+        assert_eq!(
+            Target::from_str(" HERE ").unwrap(),
+            Target::Anchor(Anchor::Here)
+        );
+        assert_eq!(
+            Target::from_str("center").unwrap(),
+            Target::Anchor(Anchor::Center)
+        );
+        assert_eq!(
+            Target::from_str("origin").unwrap(),
+            Target::Anchor(Anchor::Origin)
+        );
+    }
+
+    #[test]
+    fn invalid_target_from_str() {
+        assert_matches!(
+            Target::from_str("there").unwrap_err(),
+            BoardError::InvalidFormat(_)
+        );
+        assert_matches!(
+            Target::from_str("(3,4]").unwrap_err(),
+            BoardError::InvalidFormat(_)
+        );
+        assert_matches!(
+            Target::from_str("+1,+2,+3").unwrap_err(),
+            BoardError::Not2Dimensional(3)
+        );
+
+        let err = Target::from_str("+-3,1").unwrap_err();
+        assert_eq!(err.context().unwrap().message, "Only one sign is accepted");
+        assert_eq!(column_of(err), Some(1));
+
+        let err = Target::from_str("1,-~3").unwrap_err();
+        assert_matches!(err, BoardError::FailedParse(_));
+        assert_eq!(column_of(err), Some(3));
+
+        let err = Target::from_str("1,0b102").unwrap_err();
+        assert_eq!(
+            err.context().unwrap().message,
+            "This binary integer failed to be recognised, invalid digit '2'."
+        );
+        assert_eq!(column_of(err), Some(6));
+
+        let err = Target::from_str("(~0o,1)").unwrap_err();
+        assert_eq!(
+            err.context().unwrap().message,
+            "This octal integer has no digit after 0o"
+        );
+        assert_eq!(column_of(err), Some(4));
+
+        assert_matches!(
+            Target::from_str("+4294967296,1").unwrap_err(),
+            BoardError::FailedParse(_)
+        );
+    }
+
+    #[test]
+    fn target_resolution_wraps() {
+        let player = Position { x: 1, y: 13 };
+        let geometry = Geometry::default();
+        let resolve = |s: &str| {
+            Board::coordinate_modulo(
+                Target::from_str(s).unwrap().resolve(&player, &geometry),
+                &geometry,
+            )
+        };
+
+        assert_eq!(resolve("here"), player);
+        assert_eq!(resolve("center"), Position { x: 7, y: 7 });
+        assert_eq!(resolve("origin"), Position { x: 0, y: 0 });
+        assert_eq!(resolve("+3,-2"), Position { x: 4, y: 11 });
+        // through the edges of the torus
+        assert_eq!(resolve("~-3,~+4"), Position { x: 13, y: 2 });
+        assert_eq!(resolve("~,0b10001"), Position { x: 1, y: 2 });
+
+        // the center follows the size of the board
+        let wide = Geometry::new(40, 9, 4, 2).unwrap();
+        assert_eq!(
+            Target::from_str("centre").unwrap().resolve(&player, &wide),
+            (20, 4)
+        );
+    }
+
+    #[test]
+    fn only_absolute_targets_have_a_position() {
+        assert_eq!(
+            Target::from_str("20,3").unwrap().absolute_position(),
+            Some(Position { x: 20, y: 3 })
+        );
+        assert_eq!(Target::from_str("20,~").unwrap().absolute_position(), None);
+        assert_eq!(Target::from_str("here").unwrap().absolute_position(), None);
+    }
+}
@@ -0,0 +1,104 @@
+use super::{BoardError, ErrorContext, FromStr};
+use std::fmt;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// What the player is drawn with, a single grapheme cluster
+/// so an accented letter, a CJK character or an emoji made of several chars is one tile
+///
+/// # Attributes
+/// * `0` - the grapheme cluster, it is never empty
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Tile(String);
+
+impl Tile {
+    /// the grapheme cluster of the tile
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// the columns the tile takes in a terminal, 2 for the CJK characters and most emoji
+    pub fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    /// the tile as a lone char, the png glyphs only know those
+    ///
+    /// # Returns
+    /// * `Some(char)` - if the cluster is a single char
+    /// * `None` - if it's made of several chars, like a flag or an emoji with a skin tone
+    pub fn as_char(&self) -> Option<char> {
+        let mut chars = self.0.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    }
+}
+
+impl From<char> for Tile {
+    fn from(c: char) -> Tile {
+        Tile(c.to_string())
+    }
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Tile {
+    type Err = BoardError;
+
+    /// reads a tile, the spaces around it are ignored
+    ///
+    /// # Arguments
+    /// * `s` - the tile as the player typed it
+    ///
+    /// # Returns
+    /// * `Tile` - the single grapheme cluster
+    /// * `BoardError::InvalidFormat` - if it's not a single cluster, or if it's not drawn at all like a control char
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tile = s.trim();
+        let width = tile.width();
+        let is_drawn = width > 0 && !tile.chars().any(char::is_control);
+        if tile.graphemes(true).count() != 1 || !is_drawn || width > 2 {
+            return Err(BoardError::InvalidFormat(
+                ErrorContext::new("A tile is a single visible character or emoji").with_input(tile),
+            ));
+        }
+        Ok(Tile(tile.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matches::assert_matches;
+
+    #[test]
+    fn tiles_are_one_grapheme() {
+        assert_eq!(Tile::from_str(" @ ").unwrap(), Tile::from('@'));
+        // one cluster, several chars
+        for cluster in ["e\u{301}", "👍🏽", "🇨🇭", "👨‍👩‍👧"] {
+            let tile = Tile::from_str(cluster).unwrap();
+            assert_eq!(tile.as_str(), cluster);
+            assert_eq!(tile.as_char(), None);
+        }
+        assert_eq!(Tile::from_str("☺").unwrap().as_char(), Some('☺'));
+
+        assert_matches!(Tile::from_str("ab"), Err(BoardError::InvalidFormat(_)));
+        assert_matches!(Tile::from_str(""), Err(BoardError::InvalidFormat(_)));
+        assert_matches!(Tile::from_str("\u{7}"), Err(BoardError::InvalidFormat(_)));
+    }
+
+    #[test]
+    fn tiles_know_their_width() {
+        assert_eq!(Tile::from('@').width(), 1);
+        assert_eq!(Tile::from_str("e\u{301}").unwrap().width(), 1);
+        assert_eq!(Tile::from('宝').width(), 2);
+        assert_eq!(Tile::from('🦀').width(), 2);
+        assert_eq!(Tile::from_str("👍🏽").unwrap().width(), 2);
+    }
+}
@@ -0,0 +1,232 @@
+use super::{Geometry, Position};
+use std::collections::BTreeMap;
+
+/// What the player learnt about a searched cell
+///
+/// # Attributes
+/// * `turn` - the turn the cell was searched or cleared, see `Board::turn`
+/// * `distance` - the distance hint the search told, none if the hints were off or if an item cleared the cell
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SearchRecord {
+    pub turn: u32,
+    pub distance: Option<u32>,
+}
+
+/// How the searched cells are colored on the board, see `Board::heatmap_overlay`
+///
+/// # Variants
+/// * `Off` - the searched cells are in the player color
+/// * `Distance` - by the hint they told, the closer to the treasure the warmer
+/// * `Recency` - by when they were searched, the latest is the warmest
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Heatmap {
+    Off,
+    Distance,
+    Recency,
+}
+
+impl Heatmap {
+    /// the heatmap the command switches to, it cycles through them all
+    pub fn next(self) -> Heatmap {
+        match self {
+            Heatmap::Off => Heatmap::Distance,
+            Heatmap::Distance => Heatmap::Recency,
+            Heatmap::Recency => Heatmap::Off,
+        }
+    }
+}
+
+/// The map of where the player has already searched, and what each search told
+///
+/// the cells are packed one bit each, row after row, so even the biggest boards stay small,
+/// only the known cells have a record
+///
+/// # Attributes
+/// * `width` - the number of columns covered
+/// * `bits` - one bit per cell at `y * width + x`, set if the cell is known
+/// * `records` - the record of each known cell, by the same index
+/// * `searches` - every search in the order they were done, a cell searched twice is there twice
+#[derive(Debug, Clone)]
+pub struct Tracker {
+    width: u32,
+    bits: Vec<u64>,
+    records: BTreeMap<usize, SearchRecord>,
+    searches: Vec<(Position, SearchRecord)>,
+}
+
+impl Tracker {
+    /// a blank tracker, nothing was searched yet
+    ///
+    /// # Arguments
+    /// * `geometry` - the board the tracker covers
+    pub fn new(geometry: &Geometry) -> Tracker {
+        let cells = geometry.width as usize * geometry.height as usize;
+        Tracker {
+            width: geometry.width,
+            bits: vec![0; cells.div_ceil(64)],
+            records: BTreeMap::new(),
+            searches: Vec::new(),
+        }
+    }
+
+    /// the row-major index of a cell
+    fn index(&self, position: &Position) -> usize {
+        position.y as usize * self.width as usize + position.x as usize
+    }
+
+    /// the cell of a row-major index
+    fn position(&self, index: usize) -> Position {
+        Position {
+            x: (index % self.width as usize) as u32,
+            y: (index / self.width as usize) as u32,
+        }
+    }
+
+    /// tells if the treasure is known not to be on a cell, it was searched or cleared by an item
+    ///
+    /// # Arguments
+    /// * `position` - the in board position of the cell
+    pub fn is_searched(&self, position: &Position) -> bool {
+        let index = self.index(position);
+        self.bits[index / 64] >> (index % 64) & 1 == 1
+    }
+
+    /// every known cell and its record, row after row from the bottom
+    pub fn records(&self) -> impl Iterator<Item = (Position, &SearchRecord)> {
+        self.records
+            .iter()
+            .map(move |(&index, record)| (self.position(index), record))
+    }
+
+    /// marks a failed search, it's added to the searches and the cell keeps its record
+    ///
+    /// # Arguments
+    /// * `position` - the searched cell
+    /// * `record` - the turn and the hint of the search
+    pub fn mark_search(&mut self, position: &Position, record: SearchRecord) {
+        let index = self.index(position);
+        self.bits[index / 64] |= 1 << (index % 64);
+        self.records.insert(index, record);
+        self.searches.push((position.clone(), record));
+    }
+
+    /// marks a cell an item told the treasure isn't on
+    /// it's not a search, and a searched cell keeps the hint it told
+    ///
+    /// # Arguments
+    /// * `position` - the cleared cell
+    /// * `turn` - the turn the item was used
+    pub fn mark_cleared(&mut self, position: &Position, turn: u32) {
+        let index = self.index(position);
+        self.bits[index / 64] |= 1 << (index % 64);
+        self.records.entry(index).or_insert(SearchRecord {
+            turn,
+            distance: None,
+        });
+    }
+
+    /// every search in the order they were done
+    pub fn searches(&self) -> &[(Position, SearchRecord)] {
+        &self.searches
+    }
+
+    /// the known cells packed 8 per byte, row after row from the bottom,
+    /// cell (x, y) is the bit `i % 8` of the byte `i / 8`, with `i = y * width + x`
+    ///
+    /// # Arguments
+    /// * `geometry` - the board the tracker covers, the last byte is padded with zeros
+    pub fn packed_bits(&self, geometry: &Geometry) -> Vec<u8> {
+        let cells = geometry.width as usize * geometry.height as usize;
+        let mut bytes: Vec<u8> = self
+            .bits
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        bytes.truncate(cells.div_ceil(8));
+        bytes
+    }
+
+    /// the number of cells the treasure is known not to be on
+    pub fn searched_count(&self) -> usize {
+        self.records.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heatmaps_cycle() {
+        assert_eq!(Heatmap::Off.next(), Heatmap::Distance);
+        assert_eq!(Heatmap::Off.next().next().next(), Heatmap::Off);
+    }
+
+    #[test]
+    fn bits_are_packed_row_after_row() {
+        let geometry = Geometry::new(10, 6, 2, 2).unwrap();
+        let mut tracker = Tracker::new(&geometry);
+        tracker.mark_cleared(&Position { x: 1, y: 0 }, 1);
+        tracker.mark_cleared(&Position { x: 9, y: 5 }, 1);
+        // 60 cells in 8 bytes, the last one is (9, 5), the bit 59
+        let bytes = tracker.packed_bits(&geometry);
+        assert_eq!(bytes, vec![0b10, 0, 0, 0, 0, 0, 0, 0b1000]);
+    }
+
+    /// the last thing learnt about a cell
+    fn record_of(tracker: &Tracker, position: &Position) -> Option<SearchRecord> {
+        tracker
+            .records()
+            .find(|(known, _)| known == position)
+            .map(|(_, record)| *record)
+    }
+
+    #[test]
+    fn searches_are_kept_in_order() {
+        let mut tracker = Tracker::new(&Geometry::new(10, 6, 2, 2).unwrap());
+        let cell = Position { x: 9, y: 5 };
+        let record = |turn: u32, distance: Option<u32>| SearchRecord { turn, distance };
+
+        tracker.mark_search(&cell, record(2, Some(4)));
+        tracker.mark_cleared(&cell, 3);
+        tracker.mark_cleared(&Position { x: 0, y: 0 }, 3);
+        assert_eq!(record_of(&tracker, &cell), Some(record(2, Some(4))));
+        assert_eq!(
+            record_of(&tracker, &Position { x: 0, y: 0 }),
+            Some(record(3, None))
+        );
+        assert!(!tracker.is_searched(&Position { x: 1, y: 0 }));
+
+        tracker.mark_search(&cell, record(5, Some(3)));
+        assert_eq!(record_of(&tracker, &cell), Some(record(5, Some(3))));
+        assert_eq!(
+            tracker.searches(),
+            &[
+                (cell.clone(), record(2, Some(4))),
+                (cell.clone(), record(5, Some(3)))
+            ]
+        );
+        assert_eq!(tracker.searched_count(), 2);
+    }
+
+    #[test]
+    fn cells_are_packed_row_after_row() {
+        // 7 is not a multiple of 64, the rows straddle the words
+        let mut tracker = Tracker::new(&Geometry::new(7, 20, 2, 2).unwrap());
+        assert_eq!(tracker.bits.len(), 3);
+        for (x, y) in [(6, 9), (0, 10), (6, 19), (0, 0)] {
+            tracker.mark_cleared(&Position { x, y }, 1);
+        }
+        assert_eq!(tracker.bits[1], 0b11 << 5);
+        assert!(tracker.is_searched(&Position { x: 6, y: 19 }));
+        assert!(!tracker.is_searched(&Position { x: 5, y: 19 }));
+        assert!(!tracker.is_searched(&Position { x: 1, y: 10 }));
+        assert_eq!(
+            tracker
+                .records()
+                .map(|(position, _)| (position.x, position.y))
+                .collect::<Vec<(u32, u32)>>(),
+            vec![(0, 0), (6, 9), (0, 10), (6, 19)]
+        );
+    }
+}
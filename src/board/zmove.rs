@@ -1,4 +1,6 @@
-use super::{assert_matches, Board, BoardError, FromStr, Position, Regex};
+use super::{parser, Board, BoardError, ErrorContext, FromStr};
+use serde::Serialize;
+use std::fmt;
 
 /// A Zmove is a more intuitive way to move on a grid than teleportation
 /// It is inspired by video games, where a move is done relatively from
@@ -25,26 +27,27 @@ impl Zmove {
     /// * `Zmove` - a legal zmove
     /// * `BoardError::InvalidMove` - if the values are invalid
     pub fn new(direction: u32, speed: u32) -> Result<Zmove, BoardError> {
-        let tmp_direction: Direction;
         let tmp_speed: u32;
 
         if speed > Board::MOVE_MAX_DISTANCE {
-            return Err(BoardError::InvalidMove(format!(
-                "Your speed is too high, max is {}.",
-                Board::MOVE_MAX_DISTANCE
-            )));
+            return Err(BoardError::InvalidMove(
+                ErrorContext::new(&format!(
+                    "Your speed is too high, max is {}.",
+                    Board::MOVE_MAX_DISTANCE
+                ))
+                .expecting(1, Board::MOVE_MAX_DISTANCE as i64),
+            ));
         } else if speed == 0 {
-            return Err(BoardError::InvalidMove(format!(
-                "Your speed is 0, you can't move if you have no speed."
-            )));
+            return Err(BoardError::InvalidMove(
+                ErrorContext::new("Your speed is 0, you can't move if you have no speed.")
+                    .expecting(1, Board::MOVE_MAX_DISTANCE as i64),
+            ));
         } else {
             tmp_speed = speed;
         }
 
-        match Direction::get_direction_from_num_pad_int(direction) {
-            Ok(dir) => tmp_direction = dir,
-            Err(err) => return Err(err), // transmits error upwards
-        }
+        // transmits error upwards
+        let tmp_direction: Direction = Direction::get_direction_from_num_pad_int(direction)?;
 
         Ok(Zmove {
             direction: tmp_direction,
@@ -62,6 +65,18 @@ impl Zmove {
             self.speed as i64 * Direction::get_i64_pair_from_direction(&self.direction).1,
         )
     }
+
+    /// this returns the vectors of every cell crossed by the zmove, in order
+    /// the last one is the same as `get_vector`
+    ///
+    /// # Returns
+    /// * `Vec<(i64,i64)>` - one 2dimensial vector per step of speed
+    pub fn get_path_vectors(&self) -> Vec<(i64, i64)> {
+        let unit = Direction::get_i64_pair_from_direction(&self.direction);
+        (1..=self.speed as i64)
+            .map(|step| (step * unit.0, step * unit.1))
+            .collect()
+    }
 }
 
 impl FromStr for Zmove {
@@ -77,58 +92,25 @@ impl FromStr for Zmove {
     ///
     /// # Returns
     /// * `Zmove` - the zmove we got from str
-    /// * `BoardError::InvalidFormat` - if format is incorrect for () []
+    /// * `BoardError::InvalidFormat` - if a char is unexpected or the brackets are incorrect for () []
     /// * `BoardError::InvalidMove` - if the given parameter are incorect
     /// * `BoardError::FailedParse` - if the number is negative or not a number
     /// * `BoardError::Not2Dimensional` - if there aren't 2 values separated with ,
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let no_space_s = s.trim().replace(|x| x == ' ', ""); //we got rid of spaces
-
-        if !Regex::new(Board::PARENTHESIS_REGEX)
-            .unwrap()
-            .is_match(no_space_s.as_str())
-        {
-            return Err(
-                BoardError::InvalidFormat(
-                    "Incorrect parenthesis format, please format your zmove like this '12,13' '[12,0xc]' '(0x12,14)'".to_string(),
-                )
-            );
-        }
-
-        let clean_s = no_space_s
-            .trim_start_matches(|p| p == '(' || p == '[')
-            .trim_end_matches(|p| p == ')' || p == ']')
-            .trim();
-
-        // numeric handling
-        let number_value: Vec<&str> = clean_s.split(',').collect(); // we get the strs
-
-        if number_value.len() != 2 {
-            // if not 2 dim
-            return Err(BoardError::Not2Dimensional(number_value.len()));
-        } else {
-            // 2 dim
-
-            let zmove_to_return = Zmove::new(
-                match Position::parse_dec_or_hex(number_value[0]) {
-                    Ok(int) => int,
-                    Err(err) => return Err(err), // this err should be a failed parse
-                },
-                match Position::parse_dec_or_hex(number_value[1]) {
-                    Ok(int) => int,
-                    Err(err) => return Err(err), // this err should be a failed parse
-                },
-            ); // we get our Result<Zmove,BoardError>
-
-            match zmove_to_return {
-                Ok(zmove) => return Ok(zmove),
-                Err(err) => return Err(err), //this error should be an invalid move
-            }
-        }
+        let pair = parser::parse_number_pair(s)?;
+        let (direction, speed) = pair.values;
+
+        // the speed is checked before the direction, so it's the direction only if the speed is fine
+        let bad_column = match speed {
+            1..=Board::MOVE_MAX_DISTANCE => pair.columns.0,
+            _ => pair.columns.1,
+        };
+        Zmove::new(direction, speed).map_err(|err| err.located(&pair.input, bad_column))
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Direction {
     Right,
     UpRight,
@@ -156,10 +138,9 @@ impl Direction {
             1 => Ok(Direction::DownLeft),
             2 => Ok(Direction::Down),
             3 => Ok(Direction::DownRight),
-            _ => Err(BoardError::InvalidMove(
-                "This direction doesn't exist on a num pad [only value: 6,9,8,7,4,1,2,3]"
-                    .to_string(),
-            )),
+            _ => Err(BoardError::InvalidMove(ErrorContext::new(
+                "This direction doesn't exist on a num pad [only value: 6,9,8,7,4,1,2,3]",
+            ))),
         }
     }
 
@@ -182,11 +163,60 @@ impl Direction {
             Direction::DownRight => (1, -1),
         }
     }
+
+    /// gives the rough direction of a vector, one of the 8 directions
+    /// the vector is diagonal unless an axis is more than twice the other,
+    /// at exactly twice it's the straight direction
+    ///
+    /// # Arguments
+    /// * `vector` - the x and y of the vector, y goes up
+    ///
+    /// # Returns
+    /// * `Some(Direction)` - the closest direction
+    /// * `None` - if the vector is null
+    pub fn from_vector(vector: (i64, i64)) -> Option<Direction> {
+        let (x, y) = vector;
+        let horizontal = match x.signum() {
+            1 if y.abs() * 2 <= x.abs() => return Some(Direction::Right),
+            -1 if y.abs() * 2 <= x.abs() => return Some(Direction::Left),
+            sign => sign,
+        };
+        let vertical = match y.signum() {
+            1 if x.abs() * 2 <= y.abs() => return Some(Direction::Up),
+            -1 if x.abs() * 2 <= y.abs() => return Some(Direction::Down),
+            sign => sign,
+        };
+        match (horizontal, vertical) {
+            (1, 1) => Some(Direction::UpRight),
+            (-1, 1) => Some(Direction::UpLeft),
+            (-1, -1) => Some(Direction::DownLeft),
+            (1, -1) => Some(Direction::DownRight),
+            _ => None, // only the null vector is left
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Direction::Right => "right",
+            Direction::UpRight => "up right",
+            Direction::Up => "up",
+            Direction::UpLeft => "up left",
+            Direction::Left => "left",
+            Direction::DownLeft => "down left",
+            Direction::Down => "down",
+            Direction::DownRight => "down right",
+        };
+        write!(f, "{}", name)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::super::Position;
     use super::*;
+    use matches::assert_matches;
 
     #[test]
     fn valid_zmove_from_str_format() {
@@ -447,24 +477,31 @@ mod tests {
         // the invalid directions are already tested in valid_direction() and invalid_direction()
         // we only test the speed
         assert_eq!(
-            BoardError::InvalidMove(format!(
-                "Your speed is too high, max is {}.",
-                Board::MOVE_MAX_DISTANCE
-            )),
+            BoardError::InvalidMove(
+                ErrorContext::new(&format!(
+                    "Your speed is too high, max is {}.",
+                    Board::MOVE_MAX_DISTANCE
+                ))
+                .expecting(1, Board::MOVE_MAX_DISTANCE as i64)
+            ),
             Zmove::new(6, Board::MOVE_MAX_DISTANCE + 1).unwrap_err()
         );
         assert_eq!(
             BoardError::InvalidMove(
-                "Your speed is 0, you can't move if you have no speed.".to_string()
+                ErrorContext::new("Your speed is 0, you can't move if you have no speed.")
+                    .expecting(1, Board::MOVE_MAX_DISTANCE as i64)
             ),
             Zmove::new(6, 0).unwrap_err()
         );
 
         assert_eq!(
-            BoardError::InvalidMove(format!(
-                "Your speed is too high, max is {}.",
-                Board::MOVE_MAX_DISTANCE
-            )),
+            BoardError::InvalidMove(
+                ErrorContext::new(&format!(
+                    "Your speed is too high, max is {}.",
+                    Board::MOVE_MAX_DISTANCE
+                ))
+                .expecting(1, Board::MOVE_MAX_DISTANCE as i64)
+            ),
             Zmove::new(6, u32::MAX).unwrap_err()
         );
     }
@@ -532,4 +569,32 @@ mod tests {
             BoardError::InvalidMove(_)
         );
     }
+
+    #[test]
+    fn invalid_zmove_from_str_points_at_number() {
+        // speed too high, the speed is pointed at
+        let err = Zmove::from_str("(8,9)").unwrap_err();
+        assert_eq!(err.context().unwrap().column, Some(3));
+        assert_eq!(
+            err.context().unwrap().expected,
+            Some((1, Board::MOVE_MAX_DISTANCE as i64))
+        );
+
+        // not a num pad direction, the direction is pointed at
+        let err = Zmove::from_str("5,1").unwrap_err();
+        assert_eq!(err.context().unwrap().column, Some(0));
+    }
+
+    #[test]
+    fn rough_direction_of_vectors() {
+        assert_eq!(Direction::from_vector((0, 0)), None);
+        assert_eq!(Direction::from_vector((5, 0)), Some(Direction::Right));
+        assert_eq!(Direction::from_vector((0, -1)), Some(Direction::Down));
+        assert_eq!(Direction::from_vector((-3, 3)), Some(Direction::UpLeft));
+        assert_eq!(Direction::from_vector((3, -2)), Some(Direction::DownRight));
+        // exactly twice is straight
+        assert_eq!(Direction::from_vector((-4, 2)), Some(Direction::Left));
+        assert_eq!(Direction::from_vector((1, 2)), Some(Direction::Up));
+        assert_eq!(Direction::from_vector((-5, -11)), Some(Direction::Down));
+    }
 }
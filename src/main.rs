@@ -4,7 +4,27 @@ use board::*;
 /// The main function for our treasure search game
 ///
 /// All non-game related manipulation are put here
+/// with `--json`, the game is played with one json object per turn, for bots and tools
+/// with `--daily`, the board of the day is played, `--daily=2026-10-19` plays another day
 fn main() {
+    let is_json_mode = std::env::args().skip(1).any(|arg| arg == "--json");
+    if is_json_mode {
+        if Board::play_json_game().is_err() {
+            eprintln!("game returned an error");
+        }
+        return;
+    }
+
+    let daily_arg = std::env::args()
+        .skip(1)
+        .find(|arg| arg == "--daily" || arg.starts_with("--daily="));
+    if let Some(arg) = daily_arg {
+        if let Err(err) = Board::play_daily(arg.strip_prefix("--daily=")) {
+            eprintln!("game returned an error, {}", err);
+        }
+        return;
+    }
+
     println!("The one piece is the friends we made along the way");
     match Board::play_game() {
         Ok(_) => println!("executed withour errors"),